@@ -0,0 +1,365 @@
+use crate::manager::ModuleManager;
+use crate::module::module::Module;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// 判定两个安装属于同一个 Mod 的依据
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicateReason {
+	RemoteFileId,
+	Name,
+	SamePath, // 多个外层 .mod 文件指向同一个目录
+	Content,  // 组件的路径与哈希完全相同
+}
+
+/// 处理多余安装的方式
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicateAction {
+	Disable, // 将外层 .mod 文件改名为 .mod.disabled，可随时恢复
+	Remove,  // 删除外层 .mod 文件，Mod 目录保持不变
+}
+
+/// 一个 Mod 安装
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Installation {
+	pub name: String,
+	pub version: Option<String>,
+	pub mod_file: Option<PathBuf>, // 未生成外层 .mod 文件的创意工坊物品为 None
+	pub local_path: String,
+	pub remote_file_id: Option<String>,
+	pub hash: String,
+	pub loaded: bool, // 管理器实际使用的是这个安装
+}
+
+/// 属于同一个 Mod 的一组安装
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DuplicateGroup {
+	pub reasons: Vec<DuplicateReason>,
+	pub installations: Vec<Installation>,
+	pub active: Option<usize>, // 生效的安装，其余均为多余的安装
+}
+
+impl DuplicateGroup {
+	/// 多余的安装，即除生效安装以外的全部安装
+	pub fn redundant(&self) -> impl Iterator<Item = &Installation> {
+		self.installations
+			.iter()
+			.enumerate()
+			.filter(move |(index, _)| Some(*index) != self.active)
+			.map(|(_, installation)| installation)
+	}
+}
+
+/// 比较安装时用到的值，每个安装只计算一次
+struct Fingerprint<'a> {
+	module: &'a Module,
+	path: PathBuf, // 规范化后的 Mod 目录
+	hash: String,
+}
+
+impl<'a> Fingerprint<'a> {
+	fn new(module: &'a Module) -> Fingerprint<'a> {
+		let path = PathBuf::from(&module.meta.local_path);
+		Fingerprint {
+			module,
+			path: path.canonicalize().unwrap_or(path),
+			hash: module.content_hash(),
+		}
+	}
+
+	fn reasons(&self, other: &Fingerprint) -> Vec<DuplicateReason> {
+		let (a, b) = (&self.module.meta, &other.module.meta);
+		let mut reasons = Vec::new();
+		if a.remote_file_id.is_some() && a.remote_file_id == b.remote_file_id {
+			reasons.push(DuplicateReason::RemoteFileId);
+		}
+		if a.name == b.name {
+			reasons.push(DuplicateReason::Name);
+		}
+		if self.path == other.path {
+			reasons.push(DuplicateReason::SamePath);
+		}
+		if !self.module.components.is_empty() && self.hash == other.hash {
+			reasons.push(DuplicateReason::Content);
+		}
+		reasons
+	}
+}
+
+/// 将全部安装按是否属于同一个 Mod 分组，只返回包含多个安装的组
+///
+/// `manager` 为按名称去重后实际使用的 Mod，用于判断哪个安装生效
+pub fn find_duplicates(installations: &[Module], manager: &ModuleManager) -> Vec<DuplicateGroup> {
+	// 并查集，任意两个安装之间有判定依据即合并
+	fn root(parent: &mut [usize], mut index: usize) -> usize {
+		while parent[index] != index {
+			parent[index] = parent[parent[index]];
+			index = parent[index];
+		}
+		index
+	}
+	let fingerprints: Vec<Fingerprint> = installations.iter().map(Fingerprint::new).collect();
+	let mut parent: Vec<usize> = (0..installations.len()).collect();
+	let mut pair_reasons = Vec::new();
+	for a in 0..installations.len() {
+		for b in a + 1..installations.len() {
+			let found = fingerprints[a].reasons(&fingerprints[b]);
+			if found.is_empty() {
+				continue;
+			}
+			let (ra, rb) = (root(&mut parent, a), root(&mut parent, b));
+			parent[rb] = ra;
+			pair_reasons.push((a, found));
+		}
+	}
+
+	let mut groups = Vec::new();
+	for group_root in 0..installations.len() {
+		let members: Vec<usize> = (0..installations.len())
+			.filter(|index| root(&mut parent, *index) == group_root)
+			.collect();
+		if members.len() < 2 {
+			continue;
+		}
+		let mut reasons: Vec<DuplicateReason> = pair_reasons
+			.iter()
+			.filter(|(index, _)| members.contains(index))
+			.flat_map(|(_, found)| found.iter().copied())
+			.collect();
+		reasons.sort();
+		reasons.dedup();
+
+		let mut group_installations: Vec<Installation> = members
+			.iter()
+			.map(|index| installation(&fingerprints[*index], manager))
+			.collect();
+		// 有外层 .mod 文件的安装在前，其余按路径排列
+		group_installations.sort_by(|a, b| {
+			(a.mod_file.is_none(), &a.mod_file, &a.local_path).cmp(&(
+				b.mod_file.is_none(),
+				&b.mod_file,
+				&b.local_path,
+			))
+		});
+		let active = group_installations
+			.iter()
+			.position(|installation| installation.loaded);
+		groups.push(DuplicateGroup {
+			reasons,
+			installations: group_installations,
+			active,
+		});
+	}
+	groups
+}
+
+fn installation(fingerprint: &Fingerprint, manager: &ModuleManager) -> Installation {
+	let module = fingerprint.module;
+	let loaded = manager
+		.find_module(&module.meta.name)
+		.is_some_and(|loaded| {
+			loaded.meta.mod_file == module.meta.mod_file
+				&& loaded.meta.local_path == module.meta.local_path
+		});
+	Installation {
+		name: module.meta.name.clone(),
+		version: module.meta.version.clone(),
+		mod_file: module.meta.mod_file.clone(),
+		local_path: module.meta.local_path.clone(),
+		remote_file_id: module.meta.remote_file_id.clone(),
+		hash: fingerprint.hash.clone(),
+		loaded,
+	}
+}
+
+/// 停用或删除一个多余安装的外层 .mod 文件，生效的安装与没有外层 .mod 文件的安装不能处理
+///
+/// 组内没有生效的安装时无法确定哪些是多余的，不做任何处理
+pub fn resolve_duplicate(
+	groups: &[DuplicateGroup],
+	mod_file: &Path,
+	action: DuplicateAction,
+) -> Result<Option<PathBuf>, String> {
+	let group = groups.iter().find(|group| {
+		group
+			.installations
+			.iter()
+			.any(|installation| installation.mod_file.as_deref() == Some(mod_file))
+	});
+	if group.is_some_and(|group| group.active.is_none()) {
+		return Err(format!(
+			"No active installation in the duplicate group of {}",
+			mod_file.display()
+		));
+	}
+	let is_redundant = group.is_some_and(|group| {
+		group
+			.redundant()
+			.any(|installation| installation.mod_file.as_deref() == Some(mod_file))
+	});
+	if !is_redundant {
+		return Err(format!(
+			"{} is not a redundant installation",
+			mod_file.display()
+		));
+	}
+	match action {
+		DuplicateAction::Disable => {
+			let mut disabled = mod_file.as_os_str().to_owned();
+			disabled.push(".disabled");
+			let disabled = PathBuf::from(disabled);
+			std::fs::rename(mod_file, &disabled)
+				.map_err(|e| format!("Error disabling {}: {}", mod_file.display(), e))?;
+			Ok(Some(disabled))
+		}
+		DuplicateAction::Remove => {
+			std::fs::remove_file(mod_file)
+				.map_err(|e| format!("Error removing {}: {}", mod_file.display(), e))?;
+			Ok(None)
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::config::game::GameConfig;
+
+	fn resources() -> PathBuf {
+		Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/resources")
+	}
+
+	fn copy_dir(from: &Path, to: &Path) {
+		std::fs::create_dir_all(to).unwrap();
+		for entry in std::fs::read_dir(from).unwrap() {
+			let path = entry.unwrap().path();
+			let target = to.join(path.file_name().unwrap());
+			if path.is_dir() {
+				copy_dir(&path, &target);
+			} else {
+				std::fs::copy(&path, &target).unwrap();
+			}
+		}
+	}
+
+	/// 临时的 mod 目录，游戏目录指向测试用的 Steam 库以便读取创意工坊物品
+	fn game_config(name: &str) -> GameConfig {
+		let user_dir = std::env::temp_dir().join(format!("pmm_{}_{}", name, std::process::id()));
+		let mod_dir = user_dir.join("mod");
+		std::fs::create_dir_all(&mod_dir).unwrap();
+		let alpha = resources().join("mods/alpha");
+		let workshop = resources().join("steam/library/steamapps/workshop/content/236850");
+		let outer = |name: &str, path: &Path, extra: &str| {
+			format!("name=\"{}\"\n{}path=\"{}\"\n", name, extra, path.display())
+		};
+		std::fs::write(mod_dir.join("alpha.mod"), outer("Alpha", &alpha, "")).unwrap();
+		std::fs::write(
+			mod_dir.join("alpha_link.mod"),
+			outer("Alpha Link", &alpha, ""),
+		)
+		.unwrap();
+		// 启动器为创意工坊物品生成的外层 .mod 文件不算重复安装
+		std::fs::write(
+			mod_dir.join("ugc_1234567890.mod"),
+			outer(
+				"Delta",
+				&workshop.join("1234567890"),
+				"remote_file_id=\"1234567890\"\n",
+			),
+		)
+		.unwrap();
+		copy_dir(&workshop.join("3456789012"), &mod_dir.join("epsilon"));
+		std::fs::write(
+			mod_dir.join("epsilon.mod"),
+			outer("Epsilon", &mod_dir.join("epsilon"), ""),
+		)
+		.unwrap();
+		GameConfig::new(
+			"Europa Universalis IV".to_string(),
+			resources().join("steam/library/steamapps/common/Europa Universalis IV"),
+			mod_dir,
+		)
+	}
+
+	fn groups(game_config: &GameConfig) -> Vec<DuplicateGroup> {
+		let installations = ModuleManager::installations(game_config);
+		let manager = ModuleManager::from_game_config(game_config);
+		let mut groups = find_duplicates(&installations, &manager);
+		groups.sort_by(|a, b| a.installations[0].name.cmp(&b.installations[0].name));
+		groups
+	}
+
+	#[test]
+	fn test_find_duplicates() {
+		let game_config = game_config("duplicates");
+		let groups = groups(&game_config);
+		assert_eq!(groups.len(), 2);
+
+		let alpha = &groups[0];
+		assert_eq!(
+			alpha.reasons,
+			vec![DuplicateReason::SamePath, DuplicateReason::Content]
+		);
+		assert_eq!(alpha.active, Some(0));
+		assert_eq!(alpha.installations[0].name, "Alpha");
+		let redundant: Vec<&str> = alpha.redundant().map(|i| i.name.as_str()).collect();
+		assert_eq!(redundant, vec!["Alpha Link"]);
+
+		let epsilon = &groups[1];
+		assert_eq!(
+			epsilon.reasons,
+			vec![DuplicateReason::Name, DuplicateReason::Content]
+		);
+		// 同名时本地副本生效，创意工坊中的副本是多余的
+		assert_eq!(epsilon.active, Some(0));
+		assert!(epsilon.installations[1].mod_file.is_none());
+		assert_eq!(
+			epsilon.installations[1].remote_file_id.as_deref(),
+			Some("3456789012")
+		);
+		std::fs::remove_dir_all(game_config.user_dir()).unwrap();
+	}
+
+	#[test]
+	fn test_resolve_duplicate() {
+		let game_config = game_config("resolve_duplicates");
+		let groups = groups(&game_config);
+		let alpha_link = game_config.mod_dir.join("alpha_link.mod");
+		let alpha = game_config.mod_dir.join("alpha.mod");
+		assert!(resolve_duplicate(&groups, &alpha, DuplicateAction::Remove).is_err());
+
+		let disabled = resolve_duplicate(&groups, &alpha_link, DuplicateAction::Disable)
+			.unwrap()
+			.unwrap();
+		assert!(disabled.ends_with("alpha_link.mod.disabled"));
+		assert!(!alpha_link.exists());
+		let groups = self::groups(&game_config);
+		assert_eq!(groups.len(), 1);
+
+		std::fs::rename(&disabled, &alpha_link).unwrap();
+		let groups = self::groups(&game_config);
+		assert_eq!(
+			resolve_duplicate(&groups, &alpha_link, DuplicateAction::Remove).unwrap(),
+			None
+		);
+		assert!(!alpha_link.exists());
+		assert!(resources().join("mods/alpha/descriptor.mod").exists());
+		std::fs::remove_dir_all(game_config.user_dir()).unwrap();
+	}
+
+	#[test]
+	fn test_resolve_without_active() {
+		let game_config = game_config("resolve_inactive");
+		let mut groups = groups(&game_config);
+		let alpha_link = game_config.mod_dir.join("alpha_link.mod");
+		groups[0].active = None;
+		for action in [DuplicateAction::Disable, DuplicateAction::Remove] {
+			assert!(resolve_duplicate(&groups, &alpha_link, action).is_err());
+		}
+		assert!(alpha_link.exists());
+		assert!(game_config.mod_dir.join("alpha.mod").exists());
+		std::fs::remove_dir_all(game_config.user_dir()).unwrap();
+	}
+}
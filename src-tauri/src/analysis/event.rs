@@ -0,0 +1,184 @@
+use super::rules::GameRules;
+use crate::lang::script::{parse_script, ScriptValue};
+use crate::module::category::Category;
+use crate::module::module::Module;
+use crate::vfs::effective::{EffectiveFileSet, FileSource};
+use log::warn;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+
+/// 事件命名空间或事件 ID 的一次定义
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EventDefinition {
+	pub module: String,
+	pub relative_path: String,
+	pub line: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventCollisionKind {
+	Namespace,
+	Id,
+}
+
+/// 被多个 Mod 使用的事件命名空间或事件 ID
+#[derive(Debug, Clone, Serialize)]
+pub struct EventCollision {
+	pub kind: EventCollisionKind,
+	pub value: String,
+	pub definitions: Vec<EventDefinition>, // 按游戏加载顺序排列
+	pub winner: Option<EventDefinition>,   // 命名空间没有生效者之分
+}
+
+/// events/ 目录中的命名空间与事件 ID 索引
+#[derive(Debug, Default)]
+pub struct EventIndex {
+	namespaces: BTreeMap<String, Vec<EventDefinition>>,
+	ids: BTreeMap<String, Vec<EventDefinition>>,
+}
+
+impl EventIndex {
+	/// 为按加载顺序排列的已启用 Mod 中最终生效的事件文件建立索引
+	pub fn build(modules: &[&Module]) -> EventIndex {
+		let set = EffectiveFileSet::build(&[], modules);
+		let mut index = EventIndex::default();
+		for (relative_path, file) in set.files() {
+			let module = match &file.source {
+				FileSource::Module(module) => module,
+				FileSource::Vanilla => continue,
+			};
+			if file.component.category() != &Category::Events || !relative_path.ends_with(".txt") {
+				continue;
+			}
+			let entries = match file
+				.component
+				.read_text()
+				.map_err(|e| e.to_string())
+				.and_then(|content| parse_script(&content))
+			{
+				Ok(entries) => entries,
+				Err(e) => {
+					warn!("Error parsing {}: {}", file.component.path().display(), e);
+					continue;
+				}
+			};
+			let definition = |line| EventDefinition {
+				module: module.clone(),
+				relative_path: relative_path.to_string(),
+				line,
+			};
+
+			for entry in &entries {
+				match (&entry.key, &entry.value) {
+					(Some(key), ScriptValue::Scalar(value) | ScriptValue::Quoted(value))
+						if key == "namespace" =>
+					{
+						index
+							.namespaces
+							.entry(value.clone())
+							.or_default()
+							.push(definition(entry.line));
+					}
+					// 顶层代码块即为事件，如 country_event = { id = ... }
+					(Some(_), ScriptValue::Block(_)) => {
+						let id = entry
+							.get("id")
+							.and_then(|child| Some((child.value.as_str()?, child.line)));
+						if let Some((id, line)) = id {
+							index
+								.ids
+								.entry(id.to_string())
+								.or_default()
+								.push(definition(line));
+						}
+					}
+					_ => {}
+				}
+			}
+		}
+		index
+	}
+
+	/// 列出被多个 Mod 使用的命名空间与事件 ID，事件 ID 按 events 目录的规则给出生效的定义
+	pub fn collisions(&self, rules: &GameRules) -> Vec<EventCollision> {
+		let rule = rules.rule_for(&Category::Events.folder());
+		let namespaces = self
+			.namespaces
+			.iter()
+			.map(|(value, definitions)| (EventCollisionKind::Namespace, value, definitions, None));
+		let ids = self.ids.iter().map(|(value, definitions)| {
+			let winner = rule
+				.pick(definitions)
+				.map(|index| definitions[index].clone());
+			(EventCollisionKind::Id, value, definitions, winner)
+		});
+		namespaces
+			.chain(ids)
+			.filter(|(_, _, definitions, _)| {
+				definitions
+					.iter()
+					.map(|d| d.module.as_str())
+					.collect::<HashSet<&str>>()
+					.len() > 1
+			})
+			.map(|(kind, value, definitions, winner)| EventCollision {
+				kind,
+				value: value.clone(),
+				definitions: definitions.clone(),
+				winner,
+			})
+			.collect()
+	}
+}
+
+/// 找出按加载顺序排列的已启用 Mod 之间的事件命名空间与 ID 冲突
+pub fn find_event_collisions(modules: &[&Module], rules: &GameRules) -> Vec<EventCollision> {
+	EventIndex::build(modules).collisions(rules)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::analysis::rules::RuleTable;
+	use crate::manager::ModuleManager;
+	use std::path::Path;
+
+	#[test]
+	fn test_find_event_collisions() {
+		let mut manager = ModuleManager::new();
+		manager.load_mod_dir(Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/resources/mods"));
+		let modules = manager
+			.modules_in_order(&["Alpha".to_string(), "Beta".to_string()])
+			.unwrap();
+		let modules: Vec<&Module> = modules.iter().map(|m| m.as_ref()).collect();
+		let rules = RuleTable::embedded().for_game("Europa Universalis IV");
+		let collisions = find_event_collisions(&modules, &rules);
+
+		let summary: Vec<(EventCollisionKind, &str)> = collisions
+			.iter()
+			.map(|c| (c.kind, c.value.as_str()))
+			.collect();
+		assert_eq!(
+			summary,
+			vec![
+				(EventCollisionKind::Namespace, "alpha_event"),
+				(EventCollisionKind::Id, "1001"),
+				(EventCollisionKind::Id, "alpha_event.1"),
+			]
+		);
+
+		let namespace = &collisions[0];
+		assert!(namespace.winner.is_none());
+		assert_eq!(namespace.definitions[0].line, 1);
+		assert_eq!(namespace.definitions[1].module, "Beta");
+		assert_eq!(namespace.definitions[1].line, 2);
+
+		// EU4 的事件 ID 按 FIOS 处理，先加载的 alpha_events.txt 生效
+		let id = &collisions[2];
+		assert_eq!(id.definitions[0].line, 4);
+		assert_eq!(id.definitions[1].relative_path, "events/beta_events.txt");
+		assert_eq!(id.definitions[1].line, 12);
+		assert_eq!(id.winner.as_ref().unwrap().module, "Alpha");
+	}
+}
@@ -0,0 +1,130 @@
+use crate::module::category::Category;
+use crate::module::module::Module;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// 提供某个虚拟路径的 Mod 及其文件哈希
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FileProvider {
+	pub module: String,
+	pub hash: String,
+}
+
+/// 被多个已启用 Mod 同时提供的文件
+#[derive(Debug, Clone, Serialize)]
+pub struct FileConflict {
+	pub relative_path: String,
+	pub category: Category,
+	pub providers: Vec<FileProvider>, // 按加载顺序排列
+	pub winner: String,               // 按当前加载顺序最终生效的 Mod
+	pub identical: bool,              // 所有提供者的内容是否完全一致
+}
+
+impl FileConflict {
+	/// 内容确实不同的冲突才需要用户处理
+	pub fn is_true_conflict(&self) -> bool {
+		!self.identical
+	}
+}
+
+/// 找出所有被多个 Mod 提供的虚拟路径
+///
+/// `modules` 为按加载顺序排列的已启用 Mod，后加载的 Mod 覆盖先加载的同名文件
+pub fn find_file_conflicts(modules: &[&Module]) -> Vec<FileConflict> {
+	let mut providers: BTreeMap<&str, (Category, Vec<FileProvider>)> = BTreeMap::new();
+
+	for module in modules {
+		for component in &module.components {
+			// Mod 根目录下的 descriptor.mod、缩略图等不会被游戏加载
+			if component.category() == &Category::Root {
+				continue;
+			}
+			let entry = providers
+				.entry(component.relative_path())
+				.or_insert_with(|| (component.category().clone(), Vec::new()));
+			let provider = FileProvider {
+				module: module.meta.name.clone(),
+				hash: component.hash_hex(),
+			};
+			// 同一 Mod 重复出现时，只保留其最后的位置
+			entry.1.retain(|p| p.module != provider.module);
+			entry.1.push(provider);
+		}
+	}
+
+	providers
+		.into_iter()
+		.filter(|(_, (_, providers))| providers.len() > 1)
+		.map(|(relative_path, (category, providers))| {
+			let identical = providers
+				.windows(2)
+				.all(|pair| pair[0].hash == pair[1].hash);
+			let winner = providers.last().unwrap().module.clone();
+			FileConflict {
+				relative_path: relative_path.to_string(),
+				category,
+				providers,
+				winner,
+				identical,
+			}
+		})
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::module::component::Component;
+	use crate::module::meta::Meta;
+	use std::path::Path;
+
+	fn fixture_module(name: &str) -> Module {
+		let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+			.join("tests/resources/mods")
+			.join(name.to_lowercase());
+		let components = Component::get_components_from_dir(&dir).unwrap();
+		let meta = Meta::new(
+			name.to_string(),
+			"1.0".to_string(),
+			dir.to_str().unwrap().to_string(),
+			Vec::new(),
+		);
+		Module::new(meta, components, Vec::new(), true)
+	}
+
+	#[test]
+	fn test_find_file_conflicts() {
+		let alpha = fixture_module("Alpha");
+		let beta = fixture_module("Beta");
+		let conflicts = find_file_conflicts(&[&alpha, &beta]);
+		assert_eq!(conflicts.len(), 2);
+
+		let defines = &conflicts[0];
+		assert_eq!(defines.relative_path, "common/defines/00_max_ideas.lua");
+		assert!(defines.identical);
+		assert!(!defines.is_true_conflict());
+
+		let ideas = &conflicts[1];
+		assert_eq!(ideas.relative_path, "common/ideas/00_basic_ideas.txt");
+		assert_eq!(ideas.category, Category::Common("ideas".to_string()));
+		assert_eq!(ideas.winner, "Beta");
+		assert!(ideas.is_true_conflict());
+		assert_eq!(
+			ideas
+				.providers
+				.iter()
+				.map(|p| p.module.as_str())
+				.collect::<Vec<_>>(),
+			vec!["Alpha", "Beta"]
+		);
+	}
+
+	#[test]
+	fn test_winner_follows_load_order() {
+		let alpha = fixture_module("Alpha");
+		let beta = fixture_module("Beta");
+		let conflicts = find_file_conflicts(&[&beta, &alpha]);
+		assert!(conflicts.iter().all(|c| c.winner == "Alpha"));
+		assert!(find_file_conflicts(&[&alpha]).is_empty());
+	}
+}
@@ -0,0 +1,302 @@
+use super::rules::GameRules;
+use crate::lang::script::{parse_script, visit_entries, ScriptEntry, ScriptValue};
+use crate::module::category::Category;
+use crate::module::component::Component;
+use crate::module::module::Module;
+use crate::vfs::effective::{EffectiveFileSet, FileSource};
+use log::warn;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+
+/// 指向贴图文件的字段，进度条等类型使用 textureFile1、textureFile2
+const TEXTURE_KEYS: [&str; 3] = ["texturefile", "texturefile1", "texturefile2"];
+
+/// 界面元素名称的一次定义
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct InterfaceDefinition {
+	pub module: String,
+	pub relative_path: String,
+	pub line: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InterfaceKind {
+	Sprite, // .gfx 中的 spriteType 等
+	Window, // .gui 中 guiTypes 下的窗口
+}
+
+/// 被多个 Mod 定义的精灵图或窗口名称
+#[derive(Debug, Clone, Serialize)]
+pub struct InterfaceConflict {
+	pub kind: InterfaceKind,
+	pub name: String,
+	pub definitions: Vec<InterfaceDefinition>, // 按加载顺序排列
+	pub winner: Option<InterfaceDefinition>,
+}
+
+/// 贴图文件不存在的精灵图
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MissingTexture {
+	pub sprite: String,
+	pub texture_file: String,
+	pub definition: InterfaceDefinition,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct InterfaceReport {
+	pub conflicts: Vec<InterfaceConflict>,
+	pub missing_textures: Vec<MissingTexture>,
+}
+
+/// 代码块中某个字段的值，界面文件中的字段名不区分大小写
+fn field<'a>(children: &'a [ScriptEntry], name: &str) -> Option<&'a ScriptEntry> {
+	children.iter().find(|child| {
+		child
+			.key
+			.as_ref()
+			.is_some_and(|key| key.eq_ignore_ascii_case(name))
+	})
+}
+
+/// 贴图路径可能使用反斜杠或重复的斜杠
+fn normalize_texture_path(path: &str) -> String {
+	path.replace('\\', "/")
+		.split('/')
+		.filter(|segment| !segment.is_empty())
+		.collect::<Vec<&str>>()
+		.join("/")
+		.to_lowercase()
+}
+
+/// interface 目录中的精灵图与窗口名称索引
+#[derive(Debug, Default)]
+pub struct InterfaceIndex {
+	sprites: BTreeMap<String, Vec<InterfaceDefinition>>,
+	windows: BTreeMap<String, Vec<InterfaceDefinition>>,
+	missing_textures: Vec<MissingTexture>,
+}
+
+impl InterfaceIndex {
+	/// 为已启用 Mod 中最终生效的 .gfx 与 .gui 文件建立索引，
+	/// 贴图文件在原版与所有 Mod 叠加后的文件集合中查找
+	pub fn build(set: &EffectiveFileSet) -> InterfaceIndex {
+		let mut index = InterfaceIndex::default();
+		for (relative_path, file) in set.files() {
+			let module = match &file.source {
+				FileSource::Module(module) => module,
+				FileSource::Vanilla => continue,
+			};
+			let is_gfx = relative_path.ends_with(".gfx");
+			if file.component.category() != &Category::Interface
+				|| !(is_gfx || relative_path.ends_with(".gui"))
+			{
+				continue;
+			}
+			let entries = match file
+				.component
+				.read_text()
+				.map_err(|e| e.to_string())
+				.and_then(|content| parse_script(&content))
+			{
+				Ok(entries) => entries,
+				Err(e) => {
+					warn!("Error parsing {}: {}", file.component.path().display(), e);
+					continue;
+				}
+			};
+			let definition = |line| InterfaceDefinition {
+				module: module.clone(),
+				relative_path: relative_path.to_string(),
+				line,
+			};
+
+			if is_gfx {
+				index.add_sprites(set, &entries, definition);
+			} else {
+				index.add_windows(&entries, definition);
+			}
+		}
+		index
+	}
+
+	/// 精灵图在任意层级都是全局的，名称由 spriteType、frameAnimatedSpriteType 等类型定义
+	fn add_sprites<F: Fn(usize) -> InterfaceDefinition>(
+		&mut self,
+		set: &EffectiveFileSet,
+		entries: &[ScriptEntry],
+		definition: F,
+	) {
+		visit_entries(entries, &mut |entry| {
+			let children = match (&entry.key, &entry.value) {
+				(Some(key), ScriptValue::Block(children))
+					if key.to_lowercase().ends_with("type") =>
+				{
+					children
+				}
+				_ => return,
+			};
+			let name = match field(children, "name").and_then(|name| name.value.as_str()) {
+				Some(name) => name,
+				None => return,
+			};
+			self.sprites
+				.entry(name.to_string())
+				.or_default()
+				.push(definition(entry.line));
+
+			for texture in children.iter().filter(|child| {
+				child
+					.key
+					.as_ref()
+					.is_some_and(|key| TEXTURE_KEYS.contains(&key.to_lowercase().as_str()))
+			}) {
+				let texture_file = match texture.value.as_str() {
+					Some(texture_file) => texture_file,
+					None => continue,
+				};
+				if !set.contains(&normalize_texture_path(texture_file)) {
+					self.missing_textures.push(MissingTexture {
+						sprite: name.to_string(),
+						texture_file: texture_file.to_string(),
+						definition: definition(texture.line),
+					});
+				}
+			}
+		});
+	}
+
+	/// 只有 guiTypes 下直接定义的窗口是全局的，嵌套的窗口只在父窗口内可见
+	fn add_windows<F: Fn(usize) -> InterfaceDefinition>(
+		&mut self,
+		entries: &[ScriptEntry],
+		definition: F,
+	) {
+		for types in entries {
+			let children = match (&types.key, &types.value) {
+				(Some(key), ScriptValue::Block(children))
+					if key.eq_ignore_ascii_case("guitypes") =>
+				{
+					children
+				}
+				_ => continue,
+			};
+			for window in children {
+				let name = match &window.value {
+					ScriptValue::Block(children) => {
+						field(children, "name").and_then(|name| name.value.as_str())
+					}
+					_ => None,
+				};
+				if let Some(name) = name {
+					self.windows
+						.entry(name.to_string())
+						.or_default()
+						.push(definition(window.line));
+				}
+			}
+		}
+	}
+
+	/// 列出被多个 Mod 定义的名称，并按 interface 目录的规则给出生效的定义
+	pub fn conflicts(&self, rules: &GameRules) -> Vec<InterfaceConflict> {
+		let rule = rules.rule_for(&Category::Interface.folder());
+		let sprites = self
+			.sprites
+			.iter()
+			.map(|(name, definitions)| (InterfaceKind::Sprite, name, definitions));
+		let windows = self
+			.windows
+			.iter()
+			.map(|(name, definitions)| (InterfaceKind::Window, name, definitions));
+		sprites
+			.chain(windows)
+			.filter(|(_, _, definitions)| {
+				definitions
+					.iter()
+					.map(|d| d.module.as_str())
+					.collect::<HashSet<&str>>()
+					.len() > 1
+			})
+			.map(|(kind, name, definitions)| InterfaceConflict {
+				kind,
+				name: name.clone(),
+				definitions: definitions.clone(),
+				winner: rule
+					.pick(definitions)
+					.map(|index| definitions[index].clone()),
+			})
+			.collect()
+	}
+}
+
+/// 找出已启用 Mod 之间重复的精灵图与窗口名称，以及贴图文件缺失的精灵图
+pub fn find_interface_conflicts(
+	vanilla: &[Component],
+	modules: &[&Module],
+	rules: &GameRules,
+) -> InterfaceReport {
+	let set = EffectiveFileSet::build(vanilla, modules);
+	let index = InterfaceIndex::build(&set);
+	InterfaceReport {
+		conflicts: index.conflicts(rules),
+		missing_textures: index.missing_textures,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::analysis::rules::RuleTable;
+	use crate::manager::ModuleManager;
+	use std::path::Path;
+
+	#[test]
+	fn test_find_interface_conflicts() {
+		let resources = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/resources");
+		let vanilla = Component::get_components_from_dir(resources.join("vanilla")).unwrap();
+		let mut manager = ModuleManager::new();
+		manager.load_mod_dir(resources.join("mods"));
+		let modules = manager
+			.modules_in_order(&["Alpha".to_string(), "Beta".to_string()])
+			.unwrap();
+		let modules: Vec<&Module> = modules.iter().map(|m| m.as_ref()).collect();
+		let rules = RuleTable::embedded().for_game("Europa Universalis IV");
+		let report = find_interface_conflicts(&vanilla, &modules, &rules);
+
+		let summary: Vec<(InterfaceKind, &str)> = report
+			.conflicts
+			.iter()
+			.map(|c| (c.kind, c.name.as_str()))
+			.collect();
+		assert_eq!(
+			summary,
+			vec![
+				(InterfaceKind::Sprite, "GFX_shared_icon"),
+				(InterfaceKind::Window, "shared_window"),
+			]
+		);
+
+		// 后加载的定义全局覆盖先前的定义
+		let sprite = &report.conflicts[0];
+		assert_eq!(sprite.definitions[0].line, 7);
+		let winner = sprite.winner.as_ref().unwrap();
+		assert_eq!(winner.module, "Beta");
+		assert_eq!(winner.relative_path, "interface/beta.gfx");
+
+		// 原版与 Mod 提供的贴图都能找到，路径中的反斜杠与大小写不影响查找
+		assert_eq!(report.missing_textures.len(), 1);
+		let missing = &report.missing_textures[0];
+		assert_eq!(missing.sprite, "GFX_alpha_missing");
+		assert_eq!(missing.texture_file, "gfx/interface/alpha_missing.dds");
+		assert_eq!(missing.definition.line, 14);
+	}
+
+	#[test]
+	fn test_normalize_texture_path() {
+		assert_eq!(
+			normalize_texture_path("gfx\\interface//Ideas/Icon.dds"),
+			"gfx/interface/ideas/icon.dds"
+		);
+	}
+}
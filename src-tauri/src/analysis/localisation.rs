@@ -0,0 +1,193 @@
+use super::rules::GameRules;
+use crate::lang::localisation::parse_localisation;
+use crate::module::category::Category;
+use crate::module::module::Module;
+use crate::vfs::effective::{EffectiveFileSet, FileSource};
+use log::warn;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+
+/// 某个本地化键的一次定义
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LocalisationDefinition {
+	pub source: FileSource,
+	pub relative_path: String,
+	pub line: usize,
+	pub value: String,
+	pub replace: bool, // 是否位于 replace 子目录中
+}
+
+/// 同一语言中被多个 Mod 定义的本地化键
+#[derive(Debug, Clone, Serialize)]
+pub struct LocalisationConflict {
+	pub language: String,
+	pub key: String,
+	pub definitions: Vec<LocalisationDefinition>, // 按加载顺序排列
+	pub winner: Option<LocalisationDefinition>,
+	pub values_differ: bool,
+}
+
+/// 本地化键索引：语言 -> 键 -> 定义列表
+#[derive(Debug, Default)]
+pub struct LocalisationIndex {
+	languages: BTreeMap<String, BTreeMap<String, Vec<LocalisationDefinition>>>,
+}
+
+impl LocalisationIndex {
+	/// 为生效文件集合中的全部本地化文件建立索引
+	pub fn build(set: &EffectiveFileSet) -> LocalisationIndex {
+		let mut index = LocalisationIndex::default();
+		for (relative_path, file) in set.files() {
+			if file.component.category() != &Category::Localisation
+				|| !relative_path.ends_with(".yml")
+			{
+				continue;
+			}
+			let parsed = file
+				.component
+				.read_text()
+				.map_err(|e| e.to_string())
+				.and_then(|content| parse_localisation(&content));
+			let parsed = match parsed {
+				Ok(parsed) => parsed,
+				Err(e) => {
+					warn!("Error parsing {}: {}", file.component.path().display(), e);
+					continue;
+				}
+			};
+			let replace = relative_path.split('/').any(|segment| segment == "replace");
+			let keys = index.languages.entry(parsed.language).or_default();
+			for entry in parsed.entries {
+				keys.entry(entry.key)
+					.or_default()
+					.push(LocalisationDefinition {
+						source: file.source.clone(),
+						relative_path: relative_path.to_string(),
+						line: entry.line,
+						value: entry.value,
+						replace,
+					});
+			}
+		}
+		index
+	}
+
+	pub fn languages(&self) -> impl Iterator<Item = &str> {
+		self.languages.keys().map(|language| language.as_str())
+	}
+
+	/// 键是否在某个语言中有定义
+	pub fn defines(&self, language: &str, key: &str) -> bool {
+		self.languages
+			.get(language)
+			.is_some_and(|keys| keys.contains_key(key))
+	}
+
+	/// 键是否在任意语言中有定义
+	pub fn contains(&self, key: &str) -> bool {
+		self.languages.values().any(|keys| keys.contains_key(key))
+	}
+
+	/// 遍历所有 (语言, 键, 定义列表)
+	pub fn entries(&self) -> impl Iterator<Item = (&str, &str, &Vec<LocalisationDefinition>)> {
+		self.languages.iter().flat_map(|(language, keys)| {
+			keys.iter()
+				.map(move |(key, definitions)| (language.as_str(), key.as_str(), definitions))
+		})
+	}
+
+	/// replace 子目录中的定义优先，其余按本地化规则选出生效的定义
+	fn winner<'a>(
+		definitions: &'a [LocalisationDefinition],
+		rules: &GameRules,
+	) -> Option<&'a LocalisationDefinition> {
+		let replaced: Vec<&LocalisationDefinition> =
+			definitions.iter().filter(|d| d.replace).collect();
+		let candidates: Vec<&LocalisationDefinition> = if replaced.is_empty() {
+			definitions.iter().collect()
+		} else {
+			replaced
+		};
+		rules
+			.rule_for(&Category::Localisation.folder())
+			.pick(&candidates)
+			.map(|index| candidates[index])
+	}
+
+	/// 列出同一语言中被多个 Mod 定义的键
+	pub fn conflicts(&self, rules: &GameRules) -> Vec<LocalisationConflict> {
+		let mut conflicts = Vec::new();
+		for (language, keys) in &self.languages {
+			for (key, definitions) in keys {
+				let modules: HashSet<&str> = definitions
+					.iter()
+					.filter_map(|d| match &d.source {
+						FileSource::Module(module) => Some(module.as_str()),
+						FileSource::Vanilla => None,
+					})
+					.collect();
+				if modules.len() < 2 {
+					continue;
+				}
+				conflicts.push(LocalisationConflict {
+					language: language.clone(),
+					key: key.clone(),
+					definitions: definitions.clone(),
+					winner: LocalisationIndex::winner(definitions, rules).cloned(),
+					values_differ: definitions
+						.windows(2)
+						.any(|pair| pair[0].value != pair[1].value),
+				});
+			}
+		}
+		conflicts
+	}
+}
+
+/// 找出按加载顺序排列的已启用 Mod 之间的本地化键冲突
+pub fn find_localisation_conflicts(
+	modules: &[&Module],
+	rules: &GameRules,
+) -> Vec<LocalisationConflict> {
+	let set = EffectiveFileSet::build(&[], modules);
+	LocalisationIndex::build(&set).conflicts(rules)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::analysis::rules::RuleTable;
+	use crate::manager::ModuleManager;
+	use std::path::Path;
+
+	#[test]
+	fn test_find_localisation_conflicts() {
+		let mut manager = ModuleManager::new();
+		manager.load_mod_dir(Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/resources/mods"));
+		let modules = manager
+			.modules_in_order(&["Alpha".to_string(), "Beta".to_string()])
+			.unwrap();
+		let modules: Vec<&Module> = modules.iter().map(|m| m.as_ref()).collect();
+		let rules = RuleTable::embedded().for_game("Europa Universalis IV");
+		let conflicts = find_localisation_conflicts(&modules, &rules);
+		assert_eq!(conflicts.len(), 3);
+		assert!(conflicts.iter().all(|c| c.language == "english"));
+
+		let swe = conflicts.iter().find(|c| c.key == "SWE_ideas").unwrap();
+		assert!(!swe.values_differ);
+
+		// 同一语言中先加载的文件生效
+		let shared = conflicts.iter().find(|c| c.key == "shared_key").unwrap();
+		assert!(shared.values_differ);
+		let winner = shared.winner.as_ref().unwrap();
+		assert_eq!(winner.source, FileSource::Module("Alpha".to_string()));
+		assert_eq!(winner.line, 3);
+
+		// replace 子目录中的定义优先于普通文件
+		let replaced = conflicts.iter().find(|c| c.key == "replaced_key").unwrap();
+		let winner = replaced.winner.as_ref().unwrap();
+		assert_eq!(winner.source, FileSource::Module("Beta".to_string()));
+		assert!(winner.replace);
+		assert_eq!(winner.value, "Beta replaced value");
+	}
+}
@@ -0,0 +1,221 @@
+use super::localisation::LocalisationIndex;
+use crate::lang::script::{parse_script, visit_entries, ScriptValue};
+use crate::module::category::Category;
+use crate::module::component::Component;
+use crate::module::module::Module;
+use crate::vfs::effective::{EffectiveFileSet, FileSource};
+use log::warn;
+use serde::Serialize;
+use std::collections::BTreeSet;
+
+/// 值为本地化键的脚本字段
+const REFERENCE_KEYS: [&str; 5] = ["title", "desc", "name", "tooltip", "custom_tooltip"];
+
+/// 不是本地化键的常见取值
+const NON_KEY_VALUES: [&str; 6] = ["yes", "no", "ROOT", "FROM", "THIS", "PREV"];
+
+/// 在部分语言中缺失的本地化键
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MissingTranslation {
+	pub key: String,
+	pub present: Vec<String>,
+	pub missing: Vec<String>,
+}
+
+/// 脚本中引用了但任何 Mod 与原版都没有定义的本地化键
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MissingReference {
+	pub key: String,
+	pub relative_path: String,
+	pub line: usize,
+}
+
+/// 单个 Mod 的本地化缺失情况
+#[derive(Debug, Clone, Serialize)]
+pub struct ModuleLocalisationReport {
+	pub module: String,
+	pub missing_translations: Vec<MissingTranslation>,
+	pub missing_references: Vec<MissingReference>,
+}
+
+/// 检查每个已启用 Mod 的本地化缺失情况
+///
+/// 某个键只要在任意已加载的 Mod 或原版中有对应语言的定义即视为已翻译，
+/// 因此汉化包等翻译 Mod 提供的键不会被报告。`languages` 为空时检查所有已出现的语言
+pub fn find_missing_localisation(
+	vanilla: &[Component],
+	modules: &[&Module],
+	languages: &[String],
+) -> Vec<ModuleLocalisationReport> {
+	let set = EffectiveFileSet::build(vanilla, modules);
+	let index = LocalisationIndex::build(&set);
+	let languages: Vec<&str> = if languages.is_empty() {
+		index.languages().collect()
+	} else {
+		languages.iter().map(|language| language.as_str()).collect()
+	};
+
+	modules
+		.iter()
+		.map(|module| ModuleLocalisationReport {
+			module: module.meta.name.clone(),
+			missing_translations: missing_translations(&index, &module.meta.name, &languages),
+			missing_references: missing_references(&index, module),
+		})
+		.collect()
+}
+
+fn missing_translations(
+	index: &LocalisationIndex,
+	module_name: &str,
+	languages: &[&str],
+) -> Vec<MissingTranslation> {
+	let source = FileSource::Module(module_name.to_string());
+	let keys: BTreeSet<&str> = index
+		.entries()
+		.filter(|(_, _, definitions)| definitions.iter().any(|d| d.source == source))
+		.map(|(_, key, _)| key)
+		.collect();
+
+	keys.into_iter()
+		.filter_map(|key| {
+			let (present, missing): (Vec<&str>, Vec<&str>) = languages
+				.iter()
+				.partition(|language| index.defines(language, key));
+			if missing.is_empty() {
+				return None;
+			}
+			Some(MissingTranslation {
+				key: key.to_string(),
+				present: present.into_iter().map(str::to_string).collect(),
+				missing: missing.into_iter().map(str::to_string).collect(),
+			})
+		})
+		.collect()
+}
+
+fn missing_references(index: &LocalisationIndex, module: &Module) -> Vec<MissingReference> {
+	let mut references = Vec::new();
+	for component in &module.components {
+		let is_script = matches!(
+			component.category(),
+			Category::Events | Category::Decisions | Category::Missions | Category::Common(_)
+		);
+		if !is_script || !component.relative_path().ends_with(".txt") {
+			continue;
+		}
+		let entries = match component
+			.read_text()
+			.map_err(|e| e.to_string())
+			.and_then(|content| parse_script(&content))
+		{
+			Ok(entries) => entries,
+			Err(e) => {
+				warn!("Error parsing {}: {}", component.path().display(), e);
+				continue;
+			}
+		};
+		visit_entries(&entries, &mut |entry| {
+			let key = match (&entry.key, &entry.value) {
+				(Some(field), ScriptValue::Scalar(value) | ScriptValue::Quoted(value))
+					if REFERENCE_KEYS.contains(&field.as_str()) =>
+				{
+					value
+				}
+				_ => return,
+			};
+			if is_localisation_key(key) && !index.contains(key) {
+				references.push(MissingReference {
+					key: key.clone(),
+					relative_path: component.relative_path().to_string(),
+					line: entry.line,
+				});
+			}
+		});
+	}
+	references
+}
+
+/// 排除数字、布尔值、作用域、变量以及直接写在脚本中的文本
+fn is_localisation_key(value: &str) -> bool {
+	!value.is_empty()
+		&& !NON_KEY_VALUES.contains(&value)
+		&& value.parse::<f64>().is_err()
+		&& !value.contains(|c: char| c.is_whitespace() || matches!(c, ':' | '@' | '[' | '$'))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::manager::ModuleManager;
+	use std::path::Path;
+
+	#[test]
+	fn test_find_missing_localisation() {
+		let resources = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/resources");
+		let vanilla = Component::get_components_from_dir(resources.join("vanilla")).unwrap();
+		let mut manager = ModuleManager::new();
+		manager.load_mod_dir(resources.join("mods"));
+		let modules = manager
+			.modules_in_order(&["Alpha".to_string(), "Beta".to_string()])
+			.unwrap();
+		let modules: Vec<&Module> = modules.iter().map(|m| m.as_ref()).collect();
+		let reports = find_missing_localisation(&vanilla, &modules, &[]);
+		assert_eq!(reports.len(), 2);
+
+		let alpha = &reports[0];
+		assert_eq!(alpha.module, "Alpha");
+		let description = alpha
+			.missing_translations
+			.iter()
+			.find(|t| t.key == "alpha_event.1.d")
+			.unwrap();
+		assert_eq!(description.present, vec!["english"]);
+		assert_eq!(description.missing, vec!["german"]);
+		assert!(alpha
+			.missing_translations
+			.iter()
+			.all(|t| t.key != "alpha_event.1.t"));
+
+		let mut missing: Vec<(&str, usize)> = alpha
+			.missing_references
+			.iter()
+			.map(|r| (r.key.as_str(), r.line))
+			.collect();
+		missing.sort();
+		assert_eq!(
+			missing,
+			vec![("alpha_event.1.a", 14), ("alpha_tooltip_missing", 15)]
+		);
+		assert!(alpha
+			.missing_references
+			.iter()
+			.all(|r| r.relative_path == "events/alpha_events.txt"));
+
+		assert!(reports[1].missing_references.is_empty());
+	}
+
+	#[test]
+	fn test_requested_languages() {
+		let mut manager = ModuleManager::new();
+		manager.load_mod_dir(Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/resources/mods"));
+		let modules = manager.modules_in_order(&["Beta".to_string()]).unwrap();
+		let modules: Vec<&Module> = modules.iter().map(|m| m.as_ref()).collect();
+		let reports = find_missing_localisation(&[], &modules, &["simp_chinese".to_string()]);
+		let beta = &reports[0];
+		assert_eq!(beta.missing_translations.len(), 3);
+		assert!(beta
+			.missing_translations
+			.iter()
+			.all(|t| t.present.is_empty() && t.missing == vec!["simp_chinese"]));
+	}
+
+	#[test]
+	fn test_is_localisation_key() {
+		assert!(is_localisation_key("alpha_event.1.t"));
+		assert!(!is_localisation_key("10"));
+		assert!(!is_localisation_key("yes"));
+		assert!(!is_localisation_key("Some literal name"));
+		assert!(!is_localisation_key("event_target:foo"));
+	}
+}
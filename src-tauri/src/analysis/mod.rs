@@ -0,0 +1,8 @@
+pub mod duplicate;
+pub mod event;
+pub mod file_conflict;
+pub mod interface;
+pub mod localisation;
+pub mod missing_localisation;
+pub mod object_conflict;
+pub mod rules;
@@ -0,0 +1,189 @@
+use super::rules::{GameRules, OverrideRule};
+use crate::lang::script::parse_script;
+use crate::module::category::Category;
+use crate::module::component::Component;
+use crate::module::module::Module;
+use crate::vfs::effective::{EffectiveFileSet, FileSource};
+use log::warn;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// 某个顶层对象的一次定义
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ObjectDefinition {
+	pub module: String,
+	pub relative_path: String,
+	pub line: usize,
+}
+
+/// 被多个 Mod 定义的同名对象
+#[derive(Debug, Clone, Serialize)]
+pub struct ObjectConflict {
+	pub folder: String,
+	pub key: String,
+	pub definitions: Vec<ObjectDefinition>, // 按游戏加载顺序排列
+	pub rule: OverrideRule,
+	pub winner: Option<ObjectDefinition>, // DUPLICATE 规则下没有唯一的生效者
+}
+
+/// common/ 下各目录中顶层对象的索引：目录 -> 对象键 -> 定义列表
+#[derive(Debug, Default)]
+pub struct ObjectIndex {
+	folders: BTreeMap<String, BTreeMap<String, Vec<ObjectDefinition>>>,
+}
+
+impl ObjectIndex {
+	/// 为按加载顺序排列的已启用 Mod 建立索引
+	///
+	/// 只索引最终生效的文件：被同名文件整体覆盖或被 replace_path 隐藏的文件不会被游戏读取
+	pub fn build(modules: &[&Module]) -> ObjectIndex {
+		let effective = EffectiveFileSet::build(&[], modules);
+		// 生效文件按虚拟路径排序，与游戏按文件名顺序加载一致
+		let files = effective
+			.files()
+			.filter(|(_, file)| is_object_file(file.component))
+			.filter_map(|(_, file)| match &file.source {
+				FileSource::Module(module) => Some((module.as_str(), file.component)),
+				FileSource::Vanilla => None,
+			});
+
+		let mut index = ObjectIndex::default();
+		for (module, component) in files {
+			let content = match component.read_text() {
+				Ok(content) => content,
+				Err(e) => {
+					warn!("Error reading {}: {}", component.path().display(), e);
+					continue;
+				}
+			};
+			let entries = match parse_script(&content) {
+				Ok(entries) => entries,
+				Err(e) => {
+					warn!("Error parsing {}: {}", component.path().display(), e);
+					continue;
+				}
+			};
+			let objects = index
+				.folders
+				.entry(component.category().folder())
+				.or_default();
+			for entry in entries {
+				// 跳过列表值与 @ 开头的脚本变量
+				let key = match entry.key {
+					Some(key) if !key.starts_with('@') => key,
+					_ => continue,
+				};
+				objects.entry(key).or_default().push(ObjectDefinition {
+					module: module.to_string(),
+					relative_path: component.relative_path().to_string(),
+					line: entry.line,
+				});
+			}
+		}
+		index
+	}
+
+	/// 某个目录中某个对象的全部定义
+	pub fn definitions(&self, folder: &str, key: &str) -> Option<&Vec<ObjectDefinition>> {
+		self.folders.get(folder)?.get(key)
+	}
+
+	/// 列出被多个 Mod 定义的对象，并按目录规则给出生效的定义
+	pub fn conflicts(&self, rules: &GameRules) -> Vec<ObjectConflict> {
+		let mut conflicts = Vec::new();
+		for (folder, objects) in &self.folders {
+			let rule = rules.rule_for(folder);
+			for (key, definitions) in objects {
+				let first = &definitions[0].module;
+				if definitions.iter().all(|d| &d.module == first) {
+					continue;
+				}
+				conflicts.push(ObjectConflict {
+					folder: folder.clone(),
+					key: key.clone(),
+					definitions: definitions.clone(),
+					rule,
+					winner: rule.pick(definitions).map(|i| definitions[i].clone()),
+				});
+			}
+		}
+		conflicts
+	}
+}
+
+/// 只有 common/ 子目录下的脚本文件才会定义顶层对象
+fn is_object_file(component: &Component) -> bool {
+	matches!(component.category(), Category::Common(sub) if !sub.is_empty())
+		&& component.relative_path().ends_with(".txt")
+}
+
+/// 找出按加载顺序排列的已启用 Mod 之间的对象级冲突
+pub fn find_object_conflicts(modules: &[&Module], rules: &GameRules) -> Vec<ObjectConflict> {
+	ObjectIndex::build(modules).conflicts(rules)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::analysis::rules::RuleTable;
+	use crate::manager::ModuleManager;
+	use std::path::Path;
+
+	fn fixture_manager() -> ModuleManager {
+		let mut manager = ModuleManager::new();
+		manager.load_mod_dir(Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/resources/mods"));
+		manager
+	}
+
+	#[test]
+	fn test_object_index() {
+		let manager = fixture_manager();
+		let modules = manager
+			.modules_in_order(&["Alpha".to_string(), "Beta".to_string()])
+			.unwrap();
+		let modules: Vec<&Module> = modules.iter().map(|m| m.as_ref()).collect();
+		let index = ObjectIndex::build(&modules);
+
+		let swe = index.definitions("common/ideas", "SWE_ideas").unwrap();
+		assert_eq!(swe.len(), 2);
+		assert_eq!(swe[0].relative_path, "common/ideas/alpha_ideas.txt");
+		assert_eq!(swe[0].line, 3);
+		assert_eq!(swe[1].module, "Beta");
+		assert_eq!(swe[1].line, 11);
+
+		// Alpha 的 00_basic_ideas.txt 被 Beta 的同名文件整体覆盖
+		let innovativeness = index
+			.definitions("common/ideas", "innovativeness_ideas")
+			.unwrap();
+		assert_eq!(innovativeness.len(), 1);
+		assert_eq!(innovativeness[0].module, "Beta");
+	}
+
+	#[test]
+	fn test_find_object_conflicts() {
+		let manager = fixture_manager();
+		let modules = manager
+			.modules_in_order(&["Beta".to_string(), "Alpha".to_string()])
+			.unwrap();
+		let modules: Vec<&Module> = modules.iter().map(|m| m.as_ref()).collect();
+		let rules = RuleTable::embedded().for_game("Europa Universalis IV");
+		let conflicts = find_object_conflicts(&modules, &rules);
+		assert_eq!(conflicts.len(), 2);
+
+		let tag = &conflicts[0];
+		assert_eq!(tag.folder, "common/country_tags");
+		assert_eq!(tag.key, "ALP");
+		assert_eq!(tag.rule, OverrideRule::Fios);
+		assert_eq!(tag.winner.as_ref().unwrap().module, "Alpha");
+
+		// 对象的生效者取决于文件名顺序，而非 Mod 加载顺序
+		let swe = &conflicts[1];
+		assert_eq!(swe.key, "SWE_ideas");
+		assert_eq!(swe.rule, OverrideRule::Lios);
+		assert_eq!(swe.winner.as_ref().unwrap().module, "Beta");
+		assert_eq!(
+			swe.winner.as_ref().unwrap().relative_path,
+			"common/ideas/beta_ideas.txt"
+		);
+	}
+}
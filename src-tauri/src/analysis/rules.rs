@@ -0,0 +1,168 @@
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+/// 内置的各游戏目录规则表
+const EMBEDDED_RULES: &str = include_str!("override_rules.yaml");
+
+/// 同一对象被多次定义时游戏的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum OverrideRule {
+	Lios,      // Last In, Only Served：最后加载的定义生效
+	Fios,      // First In, Only Served：最先加载的定义生效
+	Duplicate, // 所有定义都会被加载（合并或重复生效）
+}
+
+impl OverrideRule {
+	/// 从按加载顺序排列的定义中选出生效的那一个，`Duplicate` 没有唯一的生效者
+	pub fn pick<T>(&self, definitions: &[T]) -> Option<usize> {
+		if definitions.is_empty() {
+			return None;
+		}
+		match self {
+			OverrideRule::Lios => Some(definitions.len() - 1),
+			OverrideRule::Fios => Some(0),
+			OverrideRule::Duplicate => None,
+		}
+	}
+}
+
+/// 单个游戏的目录规则
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GameRules {
+	#[serde(default)]
+	pub default: Option<OverrideRule>,
+	#[serde(default)]
+	pub folders: BTreeMap<String, OverrideRule>,
+}
+
+impl GameRules {
+	/// 查找目录的规则：先按最长前缀匹配目录表，再使用游戏默认规则，最后按 LIOS 处理
+	pub fn rule_for(&self, folder: &str) -> OverrideRule {
+		let mut prefix = folder;
+		loop {
+			if let Some(rule) = self.folders.get(prefix) {
+				return *rule;
+			}
+			match prefix.rfind('/') {
+				Some(index) => prefix = &prefix[..index],
+				None => break,
+			}
+		}
+		self.default.unwrap_or(OverrideRule::Lios)
+	}
+
+	/// 用另一份规则覆盖当前规则，`other` 中的条目优先
+	fn merge(&mut self, other: GameRules) {
+		if other.default.is_some() {
+			self.default = other.default;
+		}
+		self.folders.extend(other.folders);
+	}
+}
+
+/// 游戏名 -> 目录规则
+#[derive(Debug, Clone, Default)]
+pub struct RuleTable {
+	games: HashMap<String, GameRules>,
+}
+
+impl RuleTable {
+	pub fn from_yaml(content: &str) -> Result<RuleTable, String> {
+		let games: HashMap<String, GameRules> = serde_yaml::from_str(content)
+			.map_err(|e| format!("Error parsing override rules: {}", e))?;
+		Ok(RuleTable { games })
+	}
+
+	/// 内置规则表
+	pub fn embedded() -> RuleTable {
+		RuleTable::from_yaml(EMBEDDED_RULES).expect("embedded override rules must be valid")
+	}
+
+	/// 读取内置规则表，并在用户规则文件存在时用其覆盖
+	pub fn load<P: AsRef<Path>>(override_path: P) -> RuleTable {
+		let mut table = RuleTable::embedded();
+		let override_path = override_path.as_ref();
+		if !override_path.exists() {
+			return table;
+		}
+		info!("Loading override rules from {:?}", override_path);
+		let user_table = std::fs::read_to_string(override_path)
+			.map_err(|e| e.to_string())
+			.and_then(|content| RuleTable::from_yaml(&content));
+		match user_table {
+			Ok(user_table) => table.merge(user_table),
+			Err(e) => error!("Error loading override rules {:?}: {}", override_path, e),
+		}
+		table
+	}
+
+	pub fn merge(&mut self, other: RuleTable) {
+		for (game_name, rules) in other.games {
+			self.games.entry(game_name).or_default().merge(rules);
+		}
+	}
+
+	/// 某个游戏的规则，未知游戏返回空规则（即全部按 LIOS 处理）
+	pub fn for_game(&self, game_name: &str) -> GameRules {
+		self.games.get(game_name).cloned().unwrap_or_default()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_embedded_rules() {
+		let table = RuleTable::embedded();
+		let eu4 = table.for_game("Europa Universalis IV");
+		assert_eq!(eu4.rule_for("events"), OverrideRule::Fios);
+		assert_eq!(eu4.rule_for("common/ideas"), OverrideRule::Lios);
+		assert_eq!(eu4.rule_for("common/on_actions"), OverrideRule::Duplicate);
+
+		let hoi4 = table.for_game("Hearts of Iron IV");
+		assert_eq!(
+			hoi4.rule_for("common/national_focus"),
+			OverrideRule::Duplicate
+		);
+
+		let unknown = table.for_game("Unknown Game");
+		assert_eq!(unknown.rule_for("events"), OverrideRule::Lios);
+	}
+
+	#[test]
+	fn test_rule_prefix_match() {
+		let rules = RuleTable::from_yaml(
+			"Test:\n  default: FIOS\n  folders:\n    common: LIOS\n    common/ideas: DUPLICATE\n",
+		)
+		.unwrap()
+		.for_game("Test");
+		assert_eq!(rules.rule_for("common/ideas"), OverrideRule::Duplicate);
+		assert_eq!(rules.rule_for("common/buildings"), OverrideRule::Lios);
+		assert_eq!(rules.rule_for("events"), OverrideRule::Fios);
+	}
+
+	#[test]
+	fn test_rule_override() {
+		let mut table = RuleTable::embedded();
+		table.merge(
+			RuleTable::from_yaml("Europa Universalis IV:\n  folders:\n    events: LIOS\n").unwrap(),
+		);
+		let eu4 = table.for_game("Europa Universalis IV");
+		assert_eq!(eu4.rule_for("events"), OverrideRule::Lios);
+		assert_eq!(eu4.rule_for("common/country_tags"), OverrideRule::Fios);
+		assert_eq!(eu4.default, Some(OverrideRule::Lios));
+	}
+
+	#[test]
+	fn test_pick() {
+		let definitions = ["first", "second", "third"];
+		assert_eq!(OverrideRule::Lios.pick(&definitions), Some(2));
+		assert_eq!(OverrideRule::Fios.pick(&definitions), Some(0));
+		assert_eq!(OverrideRule::Duplicate.pick(&definitions), None);
+		assert_eq!(OverrideRule::Lios.pick::<&str>(&[]), None);
+	}
+}
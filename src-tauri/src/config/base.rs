@@ -3,15 +3,31 @@ use std::path::PathBuf;
 #[derive(Debug)]
 pub(crate) struct Config {
 	pub(crate) config_save_path: PathBuf,
+	pub(crate) override_rules_path: PathBuf,
+	pub(crate) merge_session_dir: PathBuf,
+	pub(crate) playset_save_path: PathBuf,
 }
 
 impl Config {
-	pub(crate) fn new(config_save_path: PathBuf) -> Self {
-		Self { config_save_path }
+	pub(crate) fn new(
+		config_save_path: PathBuf,
+		override_rules_path: PathBuf,
+		merge_session_dir: PathBuf,
+		playset_save_path: PathBuf,
+	) -> Self {
+		Self {
+			config_save_path,
+			override_rules_path,
+			merge_session_dir,
+			playset_save_path,
+		}
 	}
 	pub(crate) fn default() -> Self {
 		Self {
 			config_save_path: PathBuf::from("config.yaml"),
+			override_rules_path: PathBuf::from("override_rules.yaml"),
+			merge_session_dir: PathBuf::from("merge_sessions"),
+			playset_save_path: PathBuf::from("playsets.yaml"),
 		}
 	}
 }
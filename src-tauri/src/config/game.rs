@@ -24,6 +24,29 @@ impl GameConfig {
 		self.mod_dir.exists()
 	}
 
+	/// 原版游戏内容所在目录，CK3、Vic3 等新游戏的内容位于 game/ 子目录下
+	pub(crate) fn vanilla_dir(&self) -> PathBuf {
+		let content_dir = self.game_dir.join("game");
+		if content_dir.is_dir() {
+			content_dir
+		} else {
+			self.game_dir.clone()
+		}
+	}
+
+	/// 游戏用户目录，即 mod 目录的上一级
+	pub(crate) fn user_dir(&self) -> PathBuf {
+		match self.mod_dir.parent() {
+			Some(dir) => dir.to_path_buf(),
+			None => self.mod_dir.clone(),
+		}
+	}
+
+	/// 官方启动器保存播放集的数据库
+	pub(crate) fn launcher_db_path(&self) -> PathBuf {
+		self.user_dir().join("launcher-v2.sqlite")
+	}
+
 	pub(crate) fn validate(&self) -> Result<(), String> {
 		if !self.is_game_dir_exists() {
 			return Err(format!("Game directory {:?} does not exist", self.game_dir));
@@ -1,2 +1,3 @@
 pub(crate) mod base;
-pub mod game;
\ No newline at end of file
+pub mod game;
+pub mod playset;
@@ -0,0 +1,257 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// 播放集中的一个 Mod
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlaysetEntry {
+	pub module: String, // Mod 名称
+	#[serde(default = "default_enabled")]
+	pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+	true
+}
+
+impl PlaysetEntry {
+	pub fn new(module: String) -> PlaysetEntry {
+		PlaysetEntry {
+			module,
+			enabled: true,
+		}
+	}
+}
+
+/// 某个游戏的一个命名播放集，条目顺序即加载顺序
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Playset {
+	pub game_name: String,
+	pub name: String,
+	pub entries: Vec<PlaysetEntry>,
+	#[serde(default)]
+	pub notes: String,
+}
+
+impl Playset {
+	/// 已启用的 Mod，按加载顺序排列
+	pub fn enabled_modules(&self) -> Vec<String> {
+		self.entries
+			.iter()
+			.filter(|entry| entry.enabled)
+			.map(|entry| entry.module.clone())
+			.collect()
+	}
+}
+
+/// 所有游戏的播放集，与游戏配置一样保存为 YAML 文件
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PlaysetStore {
+	playsets: Vec<Playset>,
+}
+
+impl PlaysetStore {
+	pub fn load(path: &Path) -> Result<PlaysetStore, String> {
+		if !path.exists() {
+			return Ok(PlaysetStore::default());
+		}
+		let content =
+			std::fs::read(path).map_err(|e| format!("Error reading {:?}: {}", path, e))?;
+		serde_yaml::from_slice(&content).map_err(|e| format!("Error parsing {:?}: {}", path, e))
+	}
+
+	pub fn save(&self, path: &Path) -> Result<(), String> {
+		let content = serde_yaml::to_string(self).map_err(|e| e.to_string())?;
+		std::fs::write(path, content).map_err(|e| format!("Error saving {:?}: {}", path, e))
+	}
+
+	/// 某个游戏的全部播放集
+	pub fn list(&self, game_name: &str) -> Vec<&Playset> {
+		self.playsets
+			.iter()
+			.filter(|playset| playset.game_name == game_name)
+			.collect()
+	}
+
+	pub fn get(&self, game_name: &str, name: &str) -> Result<&Playset, String> {
+		self.playsets
+			.iter()
+			.find(|playset| playset.game_name == game_name && playset.name == name)
+			.ok_or_else(|| format!("Playset {} not found", name))
+	}
+
+	fn get_mut(&mut self, game_name: &str, name: &str) -> Result<&mut Playset, String> {
+		self.playsets
+			.iter_mut()
+			.find(|playset| playset.game_name == game_name && playset.name == name)
+			.ok_or_else(|| format!("Playset {} not found", name))
+	}
+
+	fn ensure_available(&self, game_name: &str, name: &str) -> Result<(), String> {
+		if name.trim().is_empty() {
+			return Err("Playset name cannot be empty".to_string());
+		}
+		match self.get(game_name, name) {
+			Ok(_) => Err(format!("Playset {} already exists", name)),
+			Err(_) => Ok(()),
+		}
+	}
+
+	pub fn create(
+		&mut self,
+		game_name: &str,
+		name: &str,
+		entries: Vec<PlaysetEntry>,
+	) -> Result<&Playset, String> {
+		self.ensure_available(game_name, name)?;
+		self.playsets.push(Playset {
+			game_name: game_name.to_string(),
+			name: name.to_string(),
+			entries,
+			notes: String::new(),
+		});
+		Ok(self.playsets.last().unwrap())
+	}
+
+	pub fn clone_playset(
+		&mut self,
+		game_name: &str,
+		name: &str,
+		new_name: &str,
+	) -> Result<&Playset, String> {
+		self.ensure_available(game_name, new_name)?;
+		let mut playset = self.get(game_name, name)?.clone();
+		playset.name = new_name.to_string();
+		self.playsets.push(playset);
+		Ok(self.playsets.last().unwrap())
+	}
+
+	pub fn rename(
+		&mut self,
+		game_name: &str,
+		name: &str,
+		new_name: &str,
+	) -> Result<&Playset, String> {
+		self.ensure_available(game_name, new_name)?;
+		let playset = self.get_mut(game_name, name)?;
+		playset.name = new_name.to_string();
+		Ok(playset)
+	}
+
+	/// 按给定的 Mod 名称重新排列条目，名称必须与原有条目一一对应
+	pub fn reorder(
+		&mut self,
+		game_name: &str,
+		name: &str,
+		order: &[String],
+	) -> Result<&Playset, String> {
+		let playset = self.get_mut(game_name, name)?;
+		let mut entries = Vec::with_capacity(order.len());
+		let mut remaining = playset.entries.clone();
+		for module in order {
+			let index = remaining
+				.iter()
+				.position(|entry| &entry.module == module)
+				.ok_or_else(|| format!("Module {} is not in playset {}", module, name))?;
+			entries.push(remaining.remove(index));
+		}
+		if let Some(entry) = remaining.first() {
+			return Err(format!(
+				"Module {} is missing from the new order",
+				entry.module
+			));
+		}
+		playset.entries = entries;
+		Ok(playset)
+	}
+
+	/// 替换条目与备注，用于启用、禁用、添加或移除 Mod
+	pub fn update(
+		&mut self,
+		game_name: &str,
+		name: &str,
+		entries: Vec<PlaysetEntry>,
+		notes: Option<String>,
+	) -> Result<&Playset, String> {
+		let playset = self.get_mut(game_name, name)?;
+		playset.entries = entries;
+		if let Some(notes) = notes {
+			playset.notes = notes;
+		}
+		Ok(playset)
+	}
+
+	pub fn delete(&mut self, game_name: &str, name: &str) -> Result<(), String> {
+		self.get(game_name, name)?;
+		self.playsets
+			.retain(|playset| !(playset.game_name == game_name && playset.name == name));
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const EU4: &str = "Europa Universalis IV";
+
+	fn entries(modules: &[&str]) -> Vec<PlaysetEntry> {
+		modules
+			.iter()
+			.map(|module| PlaysetEntry::new(module.to_string()))
+			.collect()
+	}
+
+	#[test]
+	fn test_playset_store() {
+		let mut store = PlaysetStore::default();
+		store
+			.create(EU4, "Main", entries(&["Alpha", "Beta"]))
+			.unwrap();
+		store.create("Stellaris", "Main", Vec::new()).unwrap();
+		assert!(store.create(EU4, "Main", Vec::new()).is_err());
+		assert!(store.create(EU4, " ", Vec::new()).is_err());
+
+		store.clone_playset(EU4, "Main", "Copy").unwrap();
+		store.rename(EU4, "Copy", "Multiplayer").unwrap();
+		assert!(store.rename(EU4, "Multiplayer", "Main").is_err());
+		let names: Vec<&str> = store.list(EU4).iter().map(|p| p.name.as_str()).collect();
+		assert_eq!(names, vec!["Main", "Multiplayer"]);
+
+		let order = vec!["Beta".to_string(), "Alpha".to_string()];
+		let playset = store.reorder(EU4, "Multiplayer", &order).unwrap();
+		assert_eq!(playset.enabled_modules(), order);
+		assert!(store.reorder(EU4, "Multiplayer", &order[..1]).is_err());
+		assert!(store
+			.reorder(EU4, "Multiplayer", &["Gamma".to_string()])
+			.is_err());
+
+		let mut updated = entries(&["Alpha", "Beta"]);
+		updated[0].enabled = false;
+		let playset = store
+			.update(EU4, "Main", updated, Some("Without Alpha".to_string()))
+			.unwrap();
+		assert_eq!(playset.enabled_modules(), vec!["Beta"]);
+		assert_eq!(playset.notes, "Without Alpha");
+		// 原播放集的修改不影响复制出的播放集
+		assert_eq!(store.get(EU4, "Multiplayer").unwrap().entries.len(), 2);
+
+		store.delete(EU4, "Main").unwrap();
+		assert!(store.get(EU4, "Main").is_err());
+		assert!(store.get("Stellaris", "Main").is_ok());
+		assert!(store.delete(EU4, "Main").is_err());
+	}
+
+	#[test]
+	fn test_save_and_load() {
+		let path = std::env::temp_dir().join(format!("pmm_playsets_{}.yaml", std::process::id()));
+		let mut store = PlaysetStore::default();
+		store.create(EU4, "Main", entries(&["Alpha"])).unwrap();
+		store.save(&path).unwrap();
+		assert_eq!(PlaysetStore::load(&path).unwrap(), store);
+		std::fs::remove_file(&path).unwrap();
+
+		assert_eq!(PlaysetStore::load(&path).unwrap(), PlaysetStore::default());
+		let parsed: PlaysetEntry = serde_yaml::from_str("module: Alpha").unwrap();
+		assert!(parsed.enabled);
+	}
+}
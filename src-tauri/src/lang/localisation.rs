@@ -0,0 +1,109 @@
+use nom::bytes::complete::{is_not, tag};
+use nom::character::complete::{char, digit0, space0};
+use nom::combinator::map;
+use nom::sequence::{preceded, terminated, tuple};
+use nom::IResult;
+
+/// 本地化文件中的一条记录，如 ` my_event.1.t:0 "Title"`
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocalisationEntry {
+	pub key: String,
+	pub version: Option<u32>, // CK3 等新游戏省略了版本号
+	pub value: String,
+	pub line: usize,
+}
+
+/// 一个本地化文件，语言由文件头 `l_<language>:` 决定
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocalisationFile {
+	pub language: String,
+	pub entries: Vec<LocalisationEntry>,
+}
+
+fn header(input: &str) -> IResult<&str, String> {
+	map(
+		preceded(tag("l_"), terminated(is_not(": \t"), char(':'))),
+		|language: &str| language.to_string(),
+	)(input)
+}
+
+fn entry_key(input: &str) -> IResult<&str, (String, Option<u32>)> {
+	map(
+		tuple((is_not(": \t\""), char(':'), digit0, space0)),
+		|(key, _, version, _): (&str, char, &str, &str)| (key.to_string(), version.parse().ok()),
+	)(input)
+}
+
+/// 值从第一个引号开始，到该行最后一个引号结束，值中可以包含未转义的引号
+fn entry_value(rest: &str) -> Option<String> {
+	let rest = rest.strip_prefix('"')?;
+	let end = rest.rfind('"')?;
+	Some(rest[..end].to_string())
+}
+
+pub fn parse_localisation(content: &str) -> Result<LocalisationFile, String> {
+	let mut language = None;
+	let mut entries = Vec::new();
+	// 本地化文件通常以 UTF-8 BOM 开头
+	let content = content.trim_start_matches('\u{feff}');
+
+	for (index, raw_line) in content.lines().enumerate() {
+		let line = raw_line.trim();
+		if line.is_empty() || line.starts_with('#') {
+			continue;
+		}
+		if language.is_none() {
+			match header(line) {
+				Ok((_, lang)) => {
+					language = Some(lang);
+					continue;
+				}
+				Err(_) => return Err(format!("Missing language header at line {}", index + 1)),
+			}
+		}
+		let (rest, (key, version)) = entry_key(line)
+			.map_err(|_| format!("Invalid localisation entry at line {}", index + 1))?;
+		let value = match entry_value(rest) {
+			Some(value) => value,
+			None => return Err(format!("Invalid localisation value at line {}", index + 1)),
+		};
+		entries.push(LocalisationEntry {
+			key,
+			version,
+			value,
+			line: index + 1,
+		});
+	}
+
+	match language {
+		Some(language) => Ok(LocalisationFile { language, entries }),
+		None => Err("Missing language header".to_string()),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_parse_localisation() {
+		let content = "\u{feff}l_english:\n # comment\n alpha_event.1.t:0 \"The \"Alpha\" Event\"\n alpha_event.1.d: \"Description\" # trailing\n\n EMPTY:0 \"\"\n";
+		let file = parse_localisation(content).unwrap();
+		assert_eq!(file.language, "english");
+		assert_eq!(file.entries.len(), 3);
+		assert_eq!(file.entries[0].key, "alpha_event.1.t");
+		assert_eq!(file.entries[0].version, Some(0));
+		assert_eq!(file.entries[0].value, "The \"Alpha\" Event");
+		assert_eq!(file.entries[0].line, 3);
+		assert_eq!(file.entries[1].version, None);
+		assert_eq!(file.entries[1].value, "Description");
+		assert_eq!(file.entries[2].value, "");
+	}
+
+	#[test]
+	fn test_parse_localisation_errors() {
+		assert!(parse_localisation(" key:0 \"value\"").is_err());
+		assert!(parse_localisation("l_english:\n key:0 value").is_err());
+		assert!(parse_localisation("").is_err());
+	}
+}
@@ -1 +1,4 @@
-pub mod parser;
\ No newline at end of file
+pub mod localisation;
+pub mod parser;
+pub mod script;
+pub mod vdf;
@@ -1,9 +1,9 @@
 use nom::branch::alt;
 use nom::bytes::complete::{is_not, tag};
-use nom::character::complete::{alphanumeric1, char, multispace0, multispace1, newline, space0};
+use nom::character::complete::{alphanumeric1, char, multispace0, multispace1, space0};
 use nom::combinator::map;
 use nom::multi::separated_list0;
-use nom::sequence::{delimited, separated_pair};
+use nom::sequence::{delimited, preceded, separated_pair};
 use nom::IResult;
 use std::collections::HashMap;
 
@@ -97,12 +97,19 @@ fn list(input: &str) -> IResult<&str, Vec<ParsedValue>> {
 	)(input)
 }
 
-fn top_level(input: &str) -> IResult<&str, HashMap<String, ParsedValue>> {
-	map(
+fn top_level_pairs(input: &str) -> IResult<&str, Vec<(String, ParsedValue)>> {
+	preceded(
+		multispace0,
 		separated_list0(
-			newline, // 必须是换行符来分隔每一行
+			multispace1, // 换行分隔每一行，兼容 \r\n 与空行
 			delimited(space0, kv_pair, space0),
 		),
+	)(input)
+}
+
+fn top_level(input: &str) -> IResult<&str, HashMap<String, ParsedValue>> {
+	map(
+		top_level_pairs,
 		|pairs| HashMap::from_iter(pairs.into_iter()), // 转换为 JSON
 	)(input)
 }
@@ -114,6 +121,14 @@ pub fn parse_content(content: &str) -> Result<HashMap<String, ParsedValue>, Stri
 	}
 }
 
+/// 按出现顺序返回所有键值对，保留重复的键（如多个 replace_path）
+pub fn parse_pairs(content: &str) -> Result<Vec<(String, ParsedValue)>, String> {
+	match top_level_pairs(content) {
+		Ok((_, pairs)) => Ok(pairs),
+		Err(e) => Err(format!("Failed to parse content: {:?}", e)),
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -282,4 +297,17 @@ mod tests {
 			Err(e) => panic!("parse content failed: {:?}", e),
 		}
 	}
+
+	#[test]
+	fn test_parse_pairs() {
+		let input = "name=\"Beta\"\r\n\r\nreplace_path=\"history/countries\"\r\nreplace_path=\"history/provinces\"\r\n";
+		let pairs = parse_pairs(input).unwrap();
+		let replace_paths: Vec<&str> = pairs
+			.iter()
+			.filter(|(key, _)| key == "replace_path")
+			.filter_map(|(_, value)| value.as_str())
+			.collect();
+		assert_eq!(replace_paths, vec!["history/countries", "history/provinces"]);
+		assert_eq!(pairs.len(), 3);
+	}
 }
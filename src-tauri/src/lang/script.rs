@@ -0,0 +1,367 @@
+use log::warn;
+use nom::branch::alt;
+use nom::bytes::complete::{escaped, is_not, tag};
+use nom::character::complete::{anychar, char, multispace1, not_line_ending};
+use nom::combinator::{map, opt, value};
+use nom::multi::many0;
+use nom::sequence::{delimited, preceded};
+use nom::IResult;
+use serde::{Deserialize, Serialize};
+use std::cell::Cell;
+
+/// 游戏脚本（Clausewitz 格式）中的值
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ScriptValue {
+	Scalar(String),                   // 不带引号的值，如 ADM、-0.1、yes
+	Quoted(String),                   // 带引号的字符串
+	Block(Vec<ScriptEntry>),          // { ... } 代码块
+	Tagged(String, Vec<ScriptEntry>), // 带前缀的代码块，如 rgb { 1 2 3 }
+}
+
+/// 脚本中的一项，既可以是 `key = value`，也可以是列表中的单独值
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScriptEntry {
+	pub key: Option<String>,
+	pub operator: Option<String>,
+	pub value: ScriptValue,
+	pub line: usize, // 从 1 开始的行号
+}
+
+/// 可以带代码块的值前缀
+const BLOCK_TAGS: [&str; 4] = ["rgb", "hsv", "hsv360", "hex"];
+
+impl ScriptValue {
+	pub fn as_str(&self) -> Option<&str> {
+		match self {
+			ScriptValue::Scalar(s) | ScriptValue::Quoted(s) => Some(s),
+			_ => None,
+		}
+	}
+
+	pub fn as_block(&self) -> Option<&Vec<ScriptEntry>> {
+		match self {
+			ScriptValue::Block(entries) | ScriptValue::Tagged(_, entries) => Some(entries),
+			_ => None,
+		}
+	}
+
+	fn write(&self, depth: usize, out: &mut String) {
+		match self {
+			ScriptValue::Scalar(s) => out.push_str(s),
+			ScriptValue::Quoted(s) => {
+				out.push('"');
+				out.push_str(s);
+				out.push('"');
+			}
+			ScriptValue::Block(entries) => write_block(entries, depth, out),
+			ScriptValue::Tagged(tag, entries) => {
+				out.push_str(tag);
+				out.push(' ');
+				write_block(entries, depth, out);
+			}
+		}
+	}
+}
+
+impl ScriptEntry {
+	/// 按键查找代码块中的第一个子项
+	pub fn get(&self, key: &str) -> Option<&ScriptEntry> {
+		self.value
+			.as_block()?
+			.iter()
+			.find(|entry| entry.key.as_deref() == Some(key))
+	}
+
+	/// 将该项重新输出为脚本文本，`depth` 为缩进层级
+	pub fn to_script(&self, depth: usize) -> String {
+		let mut out = String::new();
+		self.write(depth, &mut out);
+		out
+	}
+
+	fn write(&self, depth: usize, out: &mut String) {
+		out.push_str(&"\t".repeat(depth));
+		if let Some(key) = &self.key {
+			out.push_str(key);
+			out.push(' ');
+			out.push_str(self.operator.as_deref().unwrap_or("="));
+			out.push(' ');
+		}
+		self.value.write(depth, out);
+	}
+}
+
+fn write_block(entries: &[ScriptEntry], depth: usize, out: &mut String) {
+	// 只含简单值的列表写在同一行，如 { 1 2 3 }
+	let inline = entries
+		.iter()
+		.all(|entry| entry.key.is_none() && entry.value.as_block().is_none());
+	if inline {
+		out.push('{');
+		for entry in entries {
+			out.push(' ');
+			entry.value.write(0, out);
+		}
+		out.push_str(" }");
+		return;
+	}
+	out.push_str("{\n");
+	for entry in entries {
+		entry.write(depth + 1, out);
+		out.push('\n');
+	}
+	out.push_str(&"\t".repeat(depth));
+	out.push('}');
+}
+
+/// 将多个顶层项输出为完整的脚本文件
+pub fn write_script(entries: &[ScriptEntry]) -> String {
+	let mut out = String::new();
+	for entry in entries {
+		entry.write(0, &mut out);
+		out.push('\n');
+	}
+	out
+}
+
+/// 深度优先遍历所有项，包括代码块中的子项
+pub fn visit_entries<'a, F: FnMut(&'a ScriptEntry)>(entries: &'a [ScriptEntry], visitor: &mut F) {
+	for entry in entries {
+		visitor(entry);
+		if let ScriptValue::Block(children) | ScriptValue::Tagged(_, children) = &entry.value {
+			visit_entries(children, visitor);
+		}
+	}
+}
+
+fn comment(input: &str) -> IResult<&str, &str> {
+	preceded(char('#'), not_line_ending)(input)
+}
+
+/// 跳过空白与注释
+fn ignored(input: &str) -> IResult<&str, ()> {
+	value((), many0(alt((multispace1, comment))))(input)
+}
+
+fn quoted(input: &str) -> IResult<&str, String> {
+	delimited(
+		char('"'),
+		map(
+			opt(escaped(is_not("\\\""), '\\', anychar)),
+			|s: Option<&str>| s.unwrap_or_default().to_string(),
+		),
+		char('"'),
+	)(input)
+}
+
+fn bare(input: &str) -> IResult<&str, String> {
+	map(is_not(" \t\r\n{}=<>!?#\""), |s: &str| s.to_string())(input)
+}
+
+fn operator(input: &str) -> IResult<&str, String> {
+	map(
+		alt((
+			tag("=="),
+			tag("!="),
+			tag("<="),
+			tag(">="),
+			tag("?="),
+			tag("="),
+			tag("<"),
+			tag(">"),
+		)),
+		|op: &str| op.to_string(),
+	)(input)
+}
+
+struct ScriptParser<'a> {
+	source: &'a str,
+	cursor: Cell<(usize, usize)>, // 上次计算行号时的 (偏移, 行号)，避免每次从头统计
+}
+
+impl<'a> ScriptParser<'a> {
+	fn line(&self, rest: &str) -> usize {
+		let offset = self.source.len() - rest.len();
+		let (last_offset, last_line) = self.cursor.get();
+		let line = if offset >= last_offset {
+			last_line + self.source[last_offset..offset].matches('\n').count()
+		} else {
+			self.source[..offset].matches('\n').count() + 1
+		};
+		self.cursor.set((offset, line));
+		line
+	}
+
+	fn skip(&self, input: &'a str) -> &'a str {
+		match ignored(input) {
+			Ok((rest, _)) => rest,
+			Err(_) => input,
+		}
+	}
+
+	fn entries(
+		&self,
+		mut input: &'a str,
+		nested: bool,
+	) -> Result<(&'a str, Vec<ScriptEntry>), String> {
+		let mut entries = Vec::new();
+		loop {
+			input = self.skip(input);
+			if input.is_empty() {
+				if nested {
+					warn!("Missing closing brace at end of script");
+				}
+				return Ok((input, entries));
+			}
+			if let Some(rest) = input.strip_prefix('}') {
+				if nested {
+					return Ok((rest, entries));
+				}
+				// 游戏会忽略多余的右括号，这里同样跳过
+				warn!("Unmatched closing brace at line {}", self.line(input));
+				input = rest;
+				continue;
+			}
+
+			let line = self.line(input);
+			let (rest, first) = self.value(input)?;
+			let after = self.skip(rest);
+			match operator(after) {
+				Ok((rest, op)) => {
+					let key = match first {
+						ScriptValue::Scalar(s) | ScriptValue::Quoted(s) => s,
+						_ => return Err(format!("Block used as key at line {}", line)),
+					};
+					let (rest, value) = self.value(self.skip(rest))?;
+					entries.push(ScriptEntry {
+						key: Some(key),
+						operator: Some(op),
+						value,
+						line,
+					});
+					input = rest;
+				}
+				Err(_) => {
+					entries.push(ScriptEntry {
+						key: None,
+						operator: None,
+						value: first,
+						line,
+					});
+					input = rest;
+				}
+			}
+		}
+	}
+
+	fn value(&self, input: &'a str) -> Result<(&'a str, ScriptValue), String> {
+		if let Some(rest) = input.strip_prefix('{') {
+			let (rest, entries) = self.entries(rest, true)?;
+			return Ok((rest, ScriptValue::Block(entries)));
+		}
+		if let Ok((rest, s)) = quoted(input) {
+			return Ok((rest, ScriptValue::Quoted(s)));
+		}
+		if let Ok((rest, s)) = bare(input) {
+			if BLOCK_TAGS.contains(&s.to_lowercase().as_str()) {
+				if let Some(block) = self.skip(rest).strip_prefix('{') {
+					let (rest, entries) = self.entries(block, true)?;
+					return Ok((rest, ScriptValue::Tagged(s, entries)));
+				}
+			}
+			return Ok((rest, ScriptValue::Scalar(s)));
+		}
+		Err(format!("Unexpected token at line {}", self.line(input)))
+	}
+}
+
+/// 解析游戏脚本文件，返回按出现顺序排列的顶层项
+pub fn parse_script(content: &str) -> Result<Vec<ScriptEntry>, String> {
+	// 部分 Mod 文件带有 UTF-8 BOM
+	let content = content.trim_start_matches('\u{feff}');
+	let parser = ScriptParser {
+		source: content,
+		cursor: Cell::new((0, 1)),
+	};
+	parser
+		.entries(content, false)
+		.map(|(_, entries)| entries)
+		.map_err(|e| format!("Failed to parse script: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_parse_script() {
+		let content = "# comment\nSWE_ideas = {\n\tstart = {\n\t\tland_morale = 0.1 # inline\n\t}\n\ttrigger = { tag = SWE }\n}\n@cost = 10\n";
+		let entries = parse_script(content).unwrap();
+		assert_eq!(entries.len(), 2);
+
+		let ideas = &entries[0];
+		assert_eq!(ideas.key.as_deref(), Some("SWE_ideas"));
+		assert_eq!(ideas.line, 2);
+		let start = ideas.get("start").unwrap();
+		assert_eq!(start.line, 3);
+		assert_eq!(
+			start.get("land_morale").unwrap().value,
+			ScriptValue::Scalar("0.1".to_string())
+		);
+		assert_eq!(ideas.get("trigger").unwrap().line, 6);
+
+		assert_eq!(entries[1].key.as_deref(), Some("@cost"));
+		assert_eq!(entries[1].line, 8);
+	}
+
+	#[test]
+	fn test_parse_operators_and_lists() {
+		let content = "trigger = { num_of_cities >= 5 tag != FRA }\ncolor = rgb { 10 20 30 }\nlist = { \"a\" b \"\" }";
+		let entries = parse_script(content).unwrap();
+		let trigger = &entries[0];
+		assert_eq!(
+			trigger.get("num_of_cities").unwrap().operator.as_deref(),
+			Some(">=")
+		);
+		assert_eq!(trigger.get("tag").unwrap().operator.as_deref(), Some("!="));
+		assert!(
+			matches!(entries[1].value, ScriptValue::Tagged(ref tag, ref v) if tag == "rgb" && v.len() == 3)
+		);
+		assert_eq!(entries[2].value.as_block().unwrap().len(), 3);
+	}
+
+	#[test]
+	fn test_visit_entries() {
+		let entries = parse_script("a = { b = { c = d } e = f }\ng = h").unwrap();
+		let mut keys = Vec::new();
+		visit_entries(&entries, &mut |entry| keys.push(entry.key.clone().unwrap()));
+		assert_eq!(keys, vec!["a", "b", "c", "e", "g"]);
+	}
+
+	#[test]
+	fn test_unbalanced_braces() {
+		let entries = parse_script("a = { b = c }\n}\nd = e").unwrap();
+		assert_eq!(entries.len(), 2);
+		let entries = parse_script("a = { b = c").unwrap();
+		assert_eq!(entries.len(), 1);
+		assert!(parse_script("a = ").is_err());
+	}
+
+	#[test]
+	fn test_write_script() {
+		let content = "a = {\n\tb = c\n\tcolor = rgb { 1 2 3 }\n\tname = \"x y\"\n}\n";
+		let entries = parse_script(content).unwrap();
+		assert_eq!(write_script(&entries), content);
+	}
+
+	#[test]
+	fn test_write_nested_script() {
+		let content = "# comment\nSWE_ideas = { start = { land_morale = 0.1 } }\nlist = { SWE \"DAN\" }\ncolor = rgb { 10 20 30 }\nempty = { }\ntrigger = { num_of_cities >= 5 }";
+		let written = write_script(&parse_script(content).unwrap());
+		assert_eq!(
+			written,
+			"SWE_ideas = {\n\tstart = {\n\t\tland_morale = 0.1\n\t}\n}\nlist = { SWE \"DAN\" }\ncolor = rgb { 10 20 30 }\nempty = { }\ntrigger = {\n\tnum_of_cities >= 5\n}\n"
+		);
+		assert_eq!(write_script(&parse_script(&written).unwrap()), written);
+	}
+}
@@ -0,0 +1,247 @@
+use nom::branch::alt;
+use nom::bytes::complete::{escaped_transform, is_not, tag};
+use nom::character::complete::{char, multispace1, not_line_ending};
+use nom::combinator::{map, opt, value};
+use nom::multi::many0;
+use nom::sequence::{delimited, preceded};
+use nom::IResult;
+use serde::Serialize;
+
+/// Valve KeyValues（VDF/ACF）格式中的值
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub enum VdfValue {
+	String(String),
+	Object(VdfObject),
+}
+
+/// 键值对列表，键不区分大小写且允许重复，按出现顺序保存
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct VdfObject {
+	pub entries: Vec<(String, VdfValue)>,
+}
+
+impl VdfValue {
+	pub fn as_str(&self) -> Option<&str> {
+		match self {
+			VdfValue::String(s) => Some(s),
+			VdfValue::Object(_) => None,
+		}
+	}
+
+	pub fn as_object(&self) -> Option<&VdfObject> {
+		match self {
+			VdfValue::String(_) => None,
+			VdfValue::Object(object) => Some(object),
+		}
+	}
+}
+
+impl VdfObject {
+	/// 第一个匹配的键对应的值，键不区分大小写
+	pub fn get(&self, key: &str) -> Option<&VdfValue> {
+		self.entries
+			.iter()
+			.find(|(k, _)| k.eq_ignore_ascii_case(key))
+			.map(|(_, value)| value)
+	}
+
+	pub fn get_str(&self, key: &str) -> Option<&str> {
+		self.get(key)?.as_str()
+	}
+
+	pub fn get_object(&self, key: &str) -> Option<&VdfObject> {
+		self.get(key)?.as_object()
+	}
+
+	pub fn iter(&self) -> impl Iterator<Item = (&str, &VdfValue)> {
+		self.entries
+			.iter()
+			.map(|(key, value)| (key.as_str(), value))
+	}
+}
+
+fn comment(input: &str) -> IResult<&str, &str> {
+	preceded(tag("//"), not_line_ending)(input)
+}
+
+/// 跳过空白与 `//` 注释
+fn ignored(input: &str) -> IResult<&str, ()> {
+	value((), many0(alt((multispace1, comment))))(input)
+}
+
+fn quoted(input: &str) -> IResult<&str, String> {
+	delimited(
+		char('"'),
+		map(
+			opt(escaped_transform(
+				is_not("\\\""),
+				'\\',
+				alt((
+					value("\\", char('\\')),
+					value("\"", char('"')),
+					value("\n", char('n')),
+					value("\t", char('t')),
+				)),
+			)),
+			Option::unwrap_or_default,
+		),
+		char('"'),
+	)(input)
+}
+
+fn bare(input: &str) -> IResult<&str, String> {
+	map(is_not(" \t\r\n{}\"[]"), |s: &str| s.to_string())(input)
+}
+
+fn token(input: &str) -> IResult<&str, String> {
+	alt((quoted, bare))(input)
+}
+
+/// 平台条件，如 `[$WIN32]`，解析时忽略
+fn condition(input: &str) -> IResult<&str, &str> {
+	delimited(char('['), is_not("]"), char(']'))(input)
+}
+
+struct VdfParser<'a> {
+	source: &'a str,
+}
+
+impl<'a> VdfParser<'a> {
+	/// 只在出错时计算行号
+	fn line(&self, rest: &str) -> usize {
+		let offset = self.source.len() - rest.len();
+		self.source[..offset].matches('\n').count() + 1
+	}
+
+	fn skip(&self, input: &'a str) -> &'a str {
+		let input = match ignored(input) {
+			Ok((rest, _)) => rest,
+			Err(_) => input,
+		};
+		match condition(input) {
+			Ok((rest, _)) => self.skip(rest),
+			Err(_) => input,
+		}
+	}
+
+	fn object(&self, mut input: &'a str, nested: bool) -> Result<(&'a str, VdfObject), String> {
+		let mut object = VdfObject::default();
+		loop {
+			input = self.skip(input);
+			if input.is_empty() {
+				if nested {
+					return Err("Missing closing brace at end of file".to_string());
+				}
+				return Ok((input, object));
+			}
+			if let Some(rest) = input.strip_prefix('}') {
+				if nested {
+					return Ok((rest, object));
+				}
+				return Err(format!(
+					"Unmatched closing brace at line {}",
+					self.line(input)
+				));
+			}
+
+			let (rest, key) = token(input)
+				.map_err(|_| format!("Unexpected token at line {}", self.line(input)))?;
+			let rest = self.skip(rest);
+			if let Some(block) = rest.strip_prefix('{') {
+				let (rest, child) = self.object(block, true)?;
+				object.entries.push((key, VdfValue::Object(child)));
+				input = rest;
+				continue;
+			}
+			match token(rest) {
+				Ok((rest, value)) => {
+					object.entries.push((key, VdfValue::String(value)));
+					input = rest;
+				}
+				Err(_) => {
+					return Err(format!(
+						"Missing value for {} at line {}",
+						key,
+						self.line(rest)
+					))
+				}
+			}
+		}
+	}
+}
+
+/// 解析 KeyValues 文本，返回包含顶层键（如 `libraryfolders`、`AppState`）的对象
+pub fn parse_vdf(content: &str) -> Result<VdfObject, String> {
+	let content = content.trim_start_matches('\u{feff}');
+	let parser = VdfParser { source: content };
+	parser
+		.object(content, false)
+		.map(|(_, object)| object)
+		.map_err(|e| format!("Failed to parse VDF: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::path::Path;
+
+	fn fixture(name: &str) -> VdfObject {
+		let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+			.join("tests/resources/steam")
+			.join(name);
+		parse_vdf(&std::fs::read_to_string(path).unwrap()).unwrap()
+	}
+
+	#[test]
+	fn test_parse_library_folders() {
+		let root = fixture("libraryfolders.vdf");
+		let folders = root.get_object("LibraryFolders").unwrap();
+		let paths: Vec<&str> = folders
+			.iter()
+			.filter_map(|(_, folder)| folder.as_object()?.get_str("path"))
+			.collect();
+		assert_eq!(
+			paths,
+			vec!["/home/user/.local/share/Steam", "D:\\SteamLibrary"]
+		);
+		let apps = folders.get_object("0").unwrap().get_object("apps").unwrap();
+		assert_eq!(apps.get_str("236850"), Some("21474836480"));
+		assert_eq!(folders.get_object("1").unwrap().get_str("label"), Some(""));
+	}
+
+	#[test]
+	fn test_parse_manifests() {
+		let manifest = fixture("appmanifest_236850.acf");
+		let state = manifest.get_object("AppState").unwrap();
+		assert_eq!(state.get_str("installdir"), Some("Europa Universalis IV"));
+		assert_eq!(
+			state.get_object("UserConfig").unwrap().get_str("language"),
+			Some("english")
+		);
+
+		let workshop = fixture("library/steamapps/workshop/appworkshop_236850.acf");
+		let state = workshop.get_object("AppWorkshop").unwrap();
+		let installed = state.get_object("WorkshopItemsInstalled").unwrap();
+		assert_eq!(installed.entries.len(), 2);
+		assert_eq!(
+			installed.get_object("1234567890").unwrap().get_str("size"),
+			Some("52428")
+		);
+	}
+
+	#[test]
+	fn test_parse_syntax() {
+		let content = "// comment\n\"root\"\n{\n\tkey \"quoted \\\"value\\\"\" [$WIN32]\n\t\"empty\" \"\"\n\t\"KEY\" \"second\"\n\t\"child\" [$LINUX] { \"a\" \"b\" }\n}\n";
+		let root = parse_vdf(content).unwrap();
+		let object = root.get_object("root").unwrap();
+		assert_eq!(object.get_str("key"), Some("quoted \"value\""));
+		assert_eq!(object.get_str("empty"), Some(""));
+		assert_eq!(object.entries[2].0, "KEY");
+		assert_eq!(object.get_object("child").unwrap().get_str("A"), Some("b"));
+
+		assert!(parse_vdf("\"root\"\n{\n\t\"key\" \"value\"\n").is_err());
+		assert!(parse_vdf("\"key\"\n}").is_err());
+		assert!(parse_vdf("\"root\" { \"key\" }").is_err());
+		assert!(parse_vdf("").unwrap().entries.is_empty());
+	}
+}
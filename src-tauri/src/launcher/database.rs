@@ -0,0 +1,533 @@
+use crate::manager::ModuleManager;
+use rusqlite::types::Value;
+use rusqlite::{params, Connection, OpenFlags, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// 已确认写入方式兼容的数据库版本，即 knex_migrations 中最新一条迁移的名称
+const SUPPORTED_SCHEMA_VERSIONS: [&str; 1] = ["20210317093000_addPlaysetsIsRemoved.js"];
+
+/// 写回播放集时读写的表与列，版本已知时仍会检查，缺少其中任何一列都拒绝写入
+const REQUIRED_COLUMNS: [(&str, &[&str]); 3] = [
+	("playsets", &["id"]),
+	("mods", &["id"]),
+	(
+		"playsets_mods",
+		&["playsetId", "modId", "enabled", "position"],
+	),
+];
+
+/// 官方启动器播放集中的一个 Mod
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LauncherMod {
+	pub id: String,
+	pub name: String,
+	pub game_registry_id: Option<String>, // 如 mod/ugc_123456.mod
+	pub steam_id: Option<String>,
+	pub dir_path: Option<String>,
+	pub enabled: bool,
+	pub position: i64,
+	pub module: Option<String>, // 对应的本地 Mod 名称，未找到时为 None
+}
+
+/// 官方启动器中的播放集
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LauncherPlayset {
+	pub id: String,
+	pub name: String,
+	pub is_active: bool,
+	pub mods: Vec<LauncherMod>, // 按 position 排列
+}
+
+/// 写回播放集时一个 Mod 的启用状态，列表顺序即新的加载顺序
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlaysetModUpdate {
+	pub mod_id: String,
+	pub enabled: bool,
+}
+
+/// 启动器在不同版本中曾把 position 存为整数或补零的字符串
+fn integer(value: Value) -> i64 {
+	match value {
+		Value::Integer(value) => value,
+		Value::Real(value) => value as i64,
+		Value::Text(value) => value.trim().parse().unwrap_or_default(),
+		_ => 0,
+	}
+}
+
+/// 写回 position 时沿用数据库中已有的存储方式
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PositionFormat {
+	Integer,
+	Text(usize), // 补零字符串的宽度
+}
+
+impl PositionFormat {
+	/// 优先参照该播放集中已有的值，播放集为空时参照其他播放集
+	fn detect(
+		connection: &Connection,
+		playset_id: &str,
+	) -> Result<PositionFormat, rusqlite::Error> {
+		let existing: Option<Value> = connection
+			.query_row(
+				"SELECT position FROM playsets_mods WHERE position IS NOT NULL
+				ORDER BY playsetId = ?1 DESC LIMIT 1",
+				[playset_id],
+				|row| row.get(0),
+			)
+			.optional()?;
+		Ok(match existing {
+			Some(Value::Text(value)) => PositionFormat::Text(value.len()),
+			_ => PositionFormat::Integer,
+		})
+	}
+
+	fn value(self, position: usize) -> Value {
+		match self {
+			PositionFormat::Integer => Value::Integer(position as i64),
+			PositionFormat::Text(width) => {
+				Value::Text(format!("{:0width$}", position, width = width))
+			}
+		}
+	}
+}
+
+fn open_read_only(db_path: &Path) -> Result<Connection, String> {
+	Connection::open_with_flags(db_path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+		.map_err(|e| format!("Error opening {}: {}", db_path.display(), e))
+}
+
+/// 读取启动器数据库中未删除的播放集及其 Mod 列表
+pub fn read_playsets(db_path: &Path) -> Result<Vec<LauncherPlayset>, String> {
+	let connection = open_read_only(db_path)?;
+	let error = |e: rusqlite::Error| format!("Error reading {}: {}", db_path.display(), e);
+
+	let mut statement = connection
+		.prepare("SELECT id, name, isActive FROM playsets WHERE NOT COALESCE(isRemoved, 0) ORDER BY name")
+		.map_err(error)?;
+	let mut playsets = statement
+		.query_map([], |row| {
+			Ok(LauncherPlayset {
+				id: row.get(0)?,
+				name: row.get(1)?,
+				is_active: row.get::<_, Option<bool>>(2)?.unwrap_or_default(),
+				mods: Vec::new(),
+			})
+		})
+		.map_err(error)?
+		.collect::<Result<Vec<_>, _>>()
+		.map_err(error)?;
+
+	let mut statement = connection
+		.prepare(
+			"SELECT mods.id, COALESCE(mods.displayName, mods.name, mods.id), mods.gameRegistryId,
+				mods.steamId, mods.dirPath, playsets_mods.enabled, playsets_mods.position
+			FROM playsets_mods JOIN mods ON mods.id = playsets_mods.modId
+			WHERE playsets_mods.playsetId = ?1",
+		)
+		.map_err(error)?;
+	for playset in &mut playsets {
+		playset.mods = statement
+			.query_map([&playset.id], |row| {
+				Ok(LauncherMod {
+					id: row.get(0)?,
+					name: row.get(1)?,
+					game_registry_id: row.get(2)?,
+					steam_id: row.get(3)?,
+					dir_path: row.get(4)?,
+					enabled: row.get::<_, Option<bool>>(5)?.unwrap_or(true),
+					position: integer(row.get(6)?),
+					module: None,
+				})
+			})
+			.map_err(error)?
+			.collect::<Result<Vec<_>, _>>()
+			.map_err(error)?;
+		playset
+			.mods
+			.sort_by_key(|launcher_mod| launcher_mod.position);
+	}
+	Ok(playsets)
+}
+
+/// 数据库的版本，即 knex_migrations 中最新一条迁移的名称，没有迁移表或迁移记录时为 None
+fn schema_version(connection: &Connection) -> Result<Option<String>, rusqlite::Error> {
+	let has_migrations: bool = connection.query_row(
+		"SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'knex_migrations')",
+		[],
+		|row| row.get(0),
+	)?;
+	if !has_migrations {
+		return Ok(None);
+	}
+	connection
+		.query_row(
+			"SELECT name FROM knex_migrations ORDER BY id DESC LIMIT 1",
+			[],
+			|row| row.get(0),
+		)
+		.optional()
+}
+
+/// 数据库中缺少的第一个必需列，形如 `playsets_mods.position`
+fn missing_column(connection: &Connection) -> Result<Option<String>, rusqlite::Error> {
+	let mut statement = connection.prepare("SELECT name FROM pragma_table_info(?1)")?;
+	for (table, columns) in REQUIRED_COLUMNS {
+		let existing = statement
+			.query_map([table], |row| row.get(0))?
+			.collect::<Result<Vec<String>, _>>()?;
+		if let Some(column) = columns
+			.iter()
+			.find(|column| !existing.iter().any(|c| c == *column))
+		{
+			return Ok(Some(format!("{}.{}", table, column)));
+		}
+	}
+	Ok(None)
+}
+
+/// 在数据库旁边写出一份完整的备份，文件名带有时间戳
+fn backup(connection: &Connection, db_path: &Path) -> Result<PathBuf, String> {
+	let timestamp = SystemTime::now()
+		.duration_since(UNIX_EPOCH)
+		.map(|duration| duration.as_secs())
+		.unwrap_or_default();
+	let mut backup_path = db_path.as_os_str().to_owned();
+	backup_path.push(format!(".{}.bak", timestamp));
+	let backup_path = PathBuf::from(backup_path);
+	// VACUUM INTO 会包含尚未写回主文件的 WAL 内容
+	connection
+		.execute(
+			"VACUUM INTO ?1",
+			[backup_path.to_string_lossy().to_string()],
+		)
+		.map_err(|e| format!("Error backing up {}: {}", db_path.display(), e))?;
+	Ok(backup_path)
+}
+
+/// 按给定顺序写回播放集中 Mod 的位置与启用状态，返回写入前的备份路径
+///
+/// 只有数据库版本已知且包含全部必需的表与列时才会写入。列表中尚未加入播放集的 Mod 会被加入，
+/// 播放集中未出现在列表里的 Mod 保留启用状态并排在最后。写入前请确保启动器已关闭
+pub fn write_playset(
+	db_path: &Path,
+	playset_id: &str,
+	mods: &[PlaysetModUpdate],
+) -> Result<PathBuf, String> {
+	let mut connection = Connection::open_with_flags(db_path, OpenFlags::SQLITE_OPEN_READ_WRITE)
+		.map_err(|e| format!("Error opening {}: {}", db_path.display(), e))?;
+	let error = |e: rusqlite::Error| format!("Error writing {}: {}", db_path.display(), e);
+
+	let version = match schema_version(&connection).map_err(error)? {
+		Some(version) if SUPPORTED_SCHEMA_VERSIONS.contains(&version.as_str()) => version,
+		version => {
+			return Err(format!(
+				"Unsupported launcher database version {}",
+				version.unwrap_or_else(|| "unknown".to_string())
+			))
+		}
+	};
+	if let Some(column) = missing_column(&connection).map_err(error)? {
+		return Err(format!(
+			"Unsupported launcher database version {}: missing column {}",
+			version, column
+		));
+	}
+	let exists: Option<String> = connection
+		.query_row(
+			"SELECT id FROM playsets WHERE id = ?1",
+			[playset_id],
+			|row| row.get(0),
+		)
+		.optional()
+		.map_err(error)?;
+	if exists.is_none() {
+		return Err(format!("Playset {} not found", playset_id));
+	}
+	for update in mods {
+		let known: Option<String> = connection
+			.query_row(
+				"SELECT id FROM mods WHERE id = ?1",
+				[&update.mod_id],
+				|row| row.get(0),
+			)
+			.optional()
+			.map_err(error)?;
+		if known.is_none() {
+			return Err(format!(
+				"Mod {} not found in launcher database",
+				update.mod_id
+			));
+		}
+	}
+
+	let backup_path = backup(&connection, db_path)?;
+	let transaction = connection.transaction().map_err(error)?;
+	let mut remaining: Vec<String> = {
+		let mut statement = transaction
+			.prepare("SELECT modId FROM playsets_mods WHERE playsetId = ?1 ORDER BY position")
+			.map_err(error)?;
+		let ids = statement
+			.query_map([playset_id], |row| row.get(0))
+			.map_err(error)?
+			.collect::<Result<Vec<String>, _>>()
+			.map_err(error)?;
+		ids
+	};
+	remaining.retain(|id| mods.iter().all(|update| &update.mod_id != id));
+	let format = PositionFormat::detect(&transaction, playset_id).map_err(error)?;
+
+	for (position, update) in mods.iter().enumerate() {
+		transaction
+			.execute(
+				"INSERT INTO playsets_mods (playsetId, modId, enabled, position) VALUES (?1, ?2, ?3, ?4)
+				ON CONFLICT (playsetId, modId) DO UPDATE SET enabled = ?3, position = ?4",
+				params![playset_id, update.mod_id, update.enabled, format.value(position)],
+			)
+			.map_err(error)?;
+	}
+	for (offset, mod_id) in remaining.iter().enumerate() {
+		transaction
+			.execute(
+				"UPDATE playsets_mods SET position = ?3 WHERE playsetId = ?1 AND modId = ?2",
+				params![playset_id, mod_id, format.value(mods.len() + offset)],
+			)
+			.map_err(error)?;
+	}
+	transaction.commit().map_err(error)?;
+	Ok(backup_path)
+}
+
+fn folder_name(path: &str) -> Option<String> {
+	path.replace('\\', "/")
+		.trim_end_matches('/')
+		.rsplit('/')
+		.next()
+		.filter(|name| !name.is_empty())
+		.map(str::to_lowercase)
+}
+
+/// 将播放集中的 Mod 对应到管理器中的本地 Mod，先按名称匹配，再按 Mod 文件夹名匹配
+pub fn match_modules(playsets: &mut [LauncherPlayset], manager: &ModuleManager) {
+	for launcher_mod in playsets
+		.iter_mut()
+		.flat_map(|playset| playset.mods.iter_mut())
+	{
+		let by_folder = || {
+			let folder = launcher_mod.dir_path.as_deref().and_then(folder_name)?;
+			manager
+				.modules()
+				.find(|module| folder_name(&module.meta.local_path).as_ref() == Some(&folder))
+				.cloned()
+		};
+		launcher_mod.module = manager
+			.find_module(&launcher_mod.name)
+			.or_else(by_folder)
+			.map(|module| module.meta.name.clone());
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn resources() -> std::path::PathBuf {
+		Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/resources")
+	}
+
+	#[test]
+	fn test_read_playsets() {
+		let playsets = read_playsets(&resources().join("launcher/launcher-v2.sqlite")).unwrap();
+		let names: Vec<&str> = playsets.iter().map(|p| p.name.as_str()).collect();
+		assert_eq!(names, vec!["Alternative", "Main"]);
+
+		let main = &playsets[1];
+		assert!(main.is_active);
+		let mods: Vec<(&str, bool, i64)> = main
+			.mods
+			.iter()
+			.map(|m| (m.name.as_str(), m.enabled, m.position))
+			.collect();
+		assert_eq!(
+			mods,
+			vec![("Beta", true, 0), ("Alpha", true, 1), ("Gamma", false, 2)]
+		);
+		assert_eq!(main.mods[2].steam_id.as_deref(), Some("123456"));
+		assert_eq!(
+			main.mods[2].game_registry_id.as_deref(),
+			Some("mod/ugc_123456.mod")
+		);
+	}
+
+	#[test]
+	fn test_match_modules() {
+		let mut playsets = read_playsets(&resources().join("launcher/launcher-v2.sqlite")).unwrap();
+		let mut manager = ModuleManager::new();
+		manager.load_mod_dir(resources().join("mods"));
+		match_modules(&mut playsets, &manager);
+		let modules: Vec<Option<&str>> = playsets[1]
+			.mods
+			.iter()
+			.map(|m| m.module.as_deref())
+			.collect();
+		assert_eq!(modules, vec![Some("Beta"), Some("Alpha"), None]);
+	}
+
+	/// 复制一份测试数据库，避免修改测试资源
+	fn copy_database(name: &str) -> PathBuf {
+		let dir =
+			std::env::temp_dir().join(format!("pmm_launcher_{}_{}", name, std::process::id()));
+		std::fs::create_dir_all(&dir).unwrap();
+		let db_path = dir.join("launcher-v2.sqlite");
+		std::fs::copy(resources().join("launcher/launcher-v2.sqlite"), &db_path).unwrap();
+		db_path
+	}
+
+	#[test]
+	fn test_write_playset() {
+		let db_path = copy_database("write");
+		let main = "11111111-0000-0000-0000-000000000001";
+		let update = |mod_id: &str, enabled| PlaysetModUpdate {
+			mod_id: mod_id.to_string(),
+			enabled,
+		};
+		let backup_path = write_playset(
+			&db_path,
+			main,
+			&[
+				update("0a1b2c3d-0000-0000-0000-00000000a1fa", true),
+				update("0a1b2c3d-0000-0000-0000-0000000000ca", true),
+			],
+		)
+		.unwrap();
+
+		let playsets = read_playsets(&db_path).unwrap();
+		let mods: Vec<(&str, bool, i64)> = playsets[1]
+			.mods
+			.iter()
+			.map(|m| (m.name.as_str(), m.enabled, m.position))
+			.collect();
+		assert_eq!(
+			mods,
+			vec![("Alpha", true, 0), ("Gamma", true, 1), ("Beta", true, 2)]
+		);
+		// 其他播放集与备份不受影响
+		assert_eq!(playsets[0].mods.len(), 1);
+		let backup = read_playsets(&backup_path).unwrap();
+		assert_eq!(backup[1].mods[0].name, "Beta");
+
+		assert!(write_playset(&db_path, "missing", &[]).is_err());
+		assert!(write_playset(&db_path, main, &[update("missing", true)]).is_err());
+		std::fs::remove_dir_all(db_path.parent().unwrap()).unwrap();
+	}
+
+	#[test]
+	fn test_write_text_positions() {
+		let db_path = copy_database("text");
+		let connection = Connection::open(&db_path).unwrap();
+		// 新版启动器把 position 存为补零的字符串
+		connection
+			.execute_batch(
+				"ALTER TABLE playsets_mods RENAME TO playsets_mods_old;
+				CREATE TABLE playsets_mods (playsetId char(36) NOT NULL, modId char(36) NOT NULL,
+					enabled boolean DEFAULT '1', position text, PRIMARY KEY (playsetId, modId));
+				INSERT INTO playsets_mods
+					SELECT playsetId, modId, enabled, printf('%010d', position) FROM playsets_mods_old;
+				DROP TABLE playsets_mods_old;",
+			)
+			.unwrap();
+		let main = "11111111-0000-0000-0000-000000000001";
+		write_playset(
+			&db_path,
+			main,
+			&[PlaysetModUpdate {
+				mod_id: "0a1b2c3d-0000-0000-0000-0000000000ca".to_string(),
+				enabled: true,
+			}],
+		)
+		.unwrap();
+
+		let positions: Vec<(String, String)> = connection
+			.prepare("SELECT typeof(position), position FROM playsets_mods WHERE playsetId = ?1 ORDER BY position")
+			.unwrap()
+			.query_map([main], |row| Ok((row.get(0)?, row.get(1)?)))
+			.unwrap()
+			.collect::<Result<_, _>>()
+			.unwrap();
+		let expected: Vec<(String, String)> = ["0000000000", "0000000001", "0000000002"]
+			.iter()
+			.map(|position| ("text".to_string(), position.to_string()))
+			.collect();
+		assert_eq!(positions, expected);
+		let names: Vec<String> = read_playsets(&db_path).unwrap()[1]
+			.mods
+			.iter()
+			.map(|m| m.name.clone())
+			.collect();
+		assert_eq!(names, vec!["Gamma", "Beta", "Alpha"]);
+		std::fs::remove_dir_all(db_path.parent().unwrap()).unwrap();
+	}
+
+	#[test]
+	fn test_position_format() {
+		assert_eq!(PositionFormat::Integer.value(3), Value::Integer(3));
+		assert_eq!(
+			PositionFormat::Text(10).value(12),
+			Value::Text("0000000012".to_string())
+		);
+	}
+
+	/// 写入被拒绝，返回错误信息，并确认没有留下备份
+	fn refused(db_path: &Path, sql: &str) -> String {
+		Connection::open(db_path)
+			.unwrap()
+			.execute_batch(sql)
+			.unwrap();
+		let error =
+			write_playset(db_path, "11111111-0000-0000-0000-000000000001", &[]).unwrap_err();
+		let dir = db_path.parent().unwrap();
+		assert_eq!(std::fs::read_dir(dir).unwrap().count(), 1);
+		std::fs::remove_dir_all(dir).unwrap();
+		error
+	}
+
+	#[test]
+	fn test_refuse_unknown_schema() {
+		let error = refused(
+			&copy_database("future"),
+			"INSERT INTO knex_migrations (name, batch) VALUES ('29990101000000_future.js', 4)",
+		);
+		assert!(error.contains("Unsupported launcher database version 29990101000000_future.js"));
+
+		// 版本已知但缺少必需的列
+		let error = refused(
+			&copy_database("column"),
+			"ALTER TABLE playsets_mods RENAME COLUMN position TO sortOrder",
+		);
+		assert!(error.contains("20210317093000_addPlaysetsIsRemoved.js"));
+		assert!(error.contains("playsets_mods.position"));
+
+		let error = refused(&copy_database("empty"), "DELETE FROM knex_migrations");
+		assert!(error.contains("version unknown"));
+		let error = refused(&copy_database("missing"), "DROP TABLE knex_migrations");
+		assert!(error.contains("version unknown"));
+	}
+
+	#[test]
+	fn test_folder_name() {
+		assert_eq!(
+			folder_name("C:\\Users\\player\\mod\\Alpha\\").as_deref(),
+			Some("alpha")
+		);
+		assert_eq!(folder_name(""), None);
+	}
+
+	#[test]
+	fn test_integer() {
+		assert_eq!(integer(Value::Integer(3)), 3);
+		assert_eq!(integer(Value::Text("0000000012".to_string())), 12);
+		assert_eq!(integer(Value::Null), 0);
+	}
+}
@@ -0,0 +1,188 @@
+use crate::manager::ModuleManager;
+use crate::module::module::Module;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// 游戏启动时读取的 dlc_load.json
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DlcLoad {
+	#[serde(default)]
+	pub enabled_mods: Vec<String>, // 如 mod/ugc_123456.mod，顺序即加载顺序
+	#[serde(default)]
+	pub disabled_dlcs: Vec<String>,
+}
+
+/// 从 dlc_load.json 导入的加载顺序
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ImportedLoadOrder {
+	pub modules: Vec<String>, // 已找到的本地 Mod 名称，按加载顺序排列
+	pub missing: Vec<String>, // 没有对应本地 Mod 的条目
+	pub disabled_dlcs: Vec<String>,
+}
+
+/// Mod 在游戏中的注册名，即相对于用户目录的外层 .mod 文件路径
+///
+/// 由 Mod 文件夹内的 descriptor.mod 读取的 Mod 没有外层 .mod 文件，游戏无法加载，返回 None
+pub fn registry_id(module: &Module) -> Option<String> {
+	let file_name = module.meta.mod_file.as_ref()?.file_name()?.to_str()?;
+	if file_name.eq_ignore_ascii_case("descriptor.mod") {
+		return None;
+	}
+	Some(format!("mod/{}", file_name))
+}
+
+pub fn read_dlc_load(path: &Path) -> Result<DlcLoad, String> {
+	let content = std::fs::read_to_string(path)
+		.map_err(|e| format!("Error reading {}: {}", path.display(), e))?;
+	serde_json::from_str(&content).map_err(|e| format!("Error parsing {}: {}", path.display(), e))
+}
+
+/// 由按加载顺序排列的 Mod 写出用户目录下的 dlc_load.json 与 game_data.json
+///
+/// `disabled_dlcs` 为 None 时保留原文件中禁用的 DLC，game_data.json 中的其他字段保持不变
+pub fn write_dlc_load(
+	user_dir: &Path,
+	modules: &[&Module],
+	disabled_dlcs: Option<Vec<String>>,
+) -> Result<PathBuf, String> {
+	let enabled_mods = modules
+		.iter()
+		.map(|module| {
+			registry_id(module)
+				.ok_or_else(|| format!("Module {} has no .mod file", module.meta.name))
+		})
+		.collect::<Result<Vec<String>, String>>()?;
+
+	let dlc_load_path = user_dir.join("dlc_load.json");
+	let disabled_dlcs = match disabled_dlcs {
+		Some(disabled_dlcs) => disabled_dlcs,
+		None if dlc_load_path.exists() => read_dlc_load(&dlc_load_path)?.disabled_dlcs,
+		None => Vec::new(),
+	};
+	let dlc_load = DlcLoad {
+		enabled_mods: enabled_mods.clone(),
+		disabled_dlcs,
+	};
+	write_json(&dlc_load_path, &dlc_load)?;
+
+	let game_data_path = user_dir.join("game_data.json");
+	let mut game_data = match std::fs::read_to_string(&game_data_path) {
+		Ok(content) => serde_json::from_str(&content)
+			.map_err(|e| format!("Error parsing {}: {}", game_data_path.display(), e))?,
+		Err(_) => serde_json::json!({}),
+	};
+	match game_data.as_object_mut() {
+		Some(object) => {
+			object.insert("modsOrder".to_string(), serde_json::json!(enabled_mods));
+		}
+		None => return Err(format!("Invalid {}", game_data_path.display())),
+	}
+	write_json(&game_data_path, &game_data)?;
+	Ok(dlc_load_path)
+}
+
+fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
+	let content = serde_json::to_string_pretty(value).map_err(|e| e.to_string())?;
+	std::fs::write(path, content).map_err(|e| format!("Error writing {}: {}", path.display(), e))
+}
+
+/// 读取用户目录下的 dlc_load.json，并将其中的条目对应到管理器中的 Mod
+pub fn import_dlc_load(
+	user_dir: &Path,
+	manager: &ModuleManager,
+) -> Result<ImportedLoadOrder, String> {
+	let dlc_load = read_dlc_load(&user_dir.join("dlc_load.json"))?;
+	let mut imported = ImportedLoadOrder {
+		modules: Vec::new(),
+		missing: Vec::new(),
+		disabled_dlcs: dlc_load.disabled_dlcs,
+	};
+	for entry in dlc_load.enabled_mods {
+		let normalized = entry.replace('\\', "/").to_lowercase();
+		let module = manager
+			.modules()
+			.find(|module| registry_id(module).is_some_and(|id| id.to_lowercase() == normalized));
+		match module {
+			Some(module) => imported.modules.push(module.meta.name.clone()),
+			None => imported.missing.push(entry),
+		}
+	}
+	Ok(imported)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn resources() -> PathBuf {
+		Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/resources")
+	}
+
+	#[test]
+	fn test_import_dlc_load() {
+		let mut manager = ModuleManager::new();
+		manager.load_mod_dir(resources().join("mods"));
+		let imported = import_dlc_load(&resources(), &manager).unwrap();
+		assert_eq!(imported.modules, vec!["Beta", "Alpha"]);
+		assert_eq!(imported.missing, vec!["mod/ugc_999999.mod"]);
+		assert_eq!(
+			imported.disabled_dlcs,
+			vec!["dlc/dlc001_star_and_crescent.dlc"]
+		);
+	}
+
+	#[test]
+	fn test_write_dlc_load() {
+		let user_dir = std::env::temp_dir().join(format!("pmm_dlc_load_{}", std::process::id()));
+		std::fs::create_dir_all(&user_dir).unwrap();
+		std::fs::copy(
+			resources().join("dlc_load.json"),
+			user_dir.join("dlc_load.json"),
+		)
+		.unwrap();
+		std::fs::write(
+			user_dir.join("game_data.json"),
+			"{\"isEulaAccepted\": true}",
+		)
+		.unwrap();
+
+		let mut manager = ModuleManager::new();
+		manager.load_mod_dir(resources().join("mods"));
+		let modules = manager
+			.modules_in_order(&["Alpha".to_string(), "Beta".to_string()])
+			.unwrap();
+		let modules: Vec<&Module> = modules.iter().map(|m| m.as_ref()).collect();
+		let path = write_dlc_load(&user_dir, &modules, None).unwrap();
+
+		let dlc_load = read_dlc_load(&path).unwrap();
+		assert_eq!(dlc_load.enabled_mods, vec!["mod/alpha.mod", "mod/beta.mod"]);
+		assert_eq!(
+			dlc_load.disabled_dlcs,
+			vec!["dlc/dlc001_star_and_crescent.dlc"]
+		);
+		let game_data: serde_json::Value = serde_json::from_str(
+			&std::fs::read_to_string(user_dir.join("game_data.json")).unwrap(),
+		)
+		.unwrap();
+		assert_eq!(game_data["isEulaAccepted"], true);
+		assert_eq!(game_data["modsOrder"][1], "mod/beta.mod");
+
+		write_dlc_load(&user_dir, &modules[..1], Some(Vec::new())).unwrap();
+		let dlc_load = read_dlc_load(&path).unwrap();
+		assert_eq!(dlc_load.enabled_mods, vec!["mod/alpha.mod"]);
+		assert!(dlc_load.disabled_dlcs.is_empty());
+
+		// 只有 Mod 文件夹内的 descriptor.mod 时没有外层 .mod 文件
+		let inner = manager
+			.create_from_mod_file(resources().join("mods/alpha/descriptor.mod"))
+			.unwrap();
+		assert_eq!(registry_id(&inner), None);
+		let error = write_dlc_load(&user_dir, &[&inner], None).unwrap_err();
+		assert!(error.contains("has no .mod file"));
+		assert_eq!(
+			read_dlc_load(&path).unwrap().enabled_mods,
+			vec!["mod/alpha.mod"]
+		);
+		std::fs::remove_dir_all(&user_dir).unwrap();
+	}
+}
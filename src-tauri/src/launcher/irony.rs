@@ -0,0 +1,119 @@
+use crate::launcher::dlc_load::registry_id;
+use crate::manager::ModuleManager;
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::path::Path;
+
+/// Irony Mod Manager 导出的合集中记录合集信息的文件
+const EXPORT_FILE_NAME: &str = "exported.json";
+
+/// Irony Mod Manager 合集导出文件中的 JSON 元数据
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct IronyCollection {
+	pub name: String,
+	#[serde(default)]
+	pub game: Option<String>,
+	#[serde(default)]
+	pub mods: Vec<String>, // 如 mod/ugc_123456.mod，顺序即加载顺序
+	#[serde(default)]
+	pub mod_names: Vec<String>, // 与 mods 一一对应的 Mod 名称
+}
+
+/// 从 Irony 合集导入的加载顺序
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ImportedCollection {
+	pub name: String,
+	pub modules: Vec<String>, // 已找到的本地 Mod 名称，按加载顺序排列
+	pub missing: Vec<String>, // 没有对应本地 Mod 的条目，优先使用 Mod 名称
+}
+
+/// 读取合集 zip 中的 exported.json，没有时退而读取根目录下的第一个 JSON 文件
+pub fn read_collection(path: &Path) -> Result<IronyCollection, String> {
+	let error = |e: zip::result::ZipError| format!("Error reading {}: {}", path.display(), e);
+	let file = std::fs::File::open(path)
+		.map_err(|e| format!("Error opening {}: {}", path.display(), e))?;
+	let mut archive = zip::ZipArchive::new(file).map_err(error)?;
+
+	let name = match archive.index_for_name(EXPORT_FILE_NAME) {
+		Some(_) => EXPORT_FILE_NAME.to_string(),
+		None => archive
+			.file_names()
+			.filter(|name| !name.contains('/') && name.to_lowercase().ends_with(".json"))
+			.min()
+			.ok_or_else(|| format!("No collection metadata in {}", path.display()))?
+			.to_string(),
+	};
+	let mut content = String::new();
+	archive
+		.by_name(&name)
+		.map_err(error)?
+		.read_to_string(&mut content)
+		.map_err(|e| format!("Error reading {} in {}: {}", name, path.display(), e))?;
+	serde_json::from_str(content.trim_start_matches('\u{feff}'))
+		.map_err(|e| format!("Error parsing {} in {}: {}", name, path.display(), e))
+}
+
+/// 将合集条目对应到管理器中的 Mod，先按 .mod 文件匹配，再按名称匹配
+pub fn import_collection(
+	path: &Path,
+	manager: &ModuleManager,
+) -> Result<ImportedCollection, String> {
+	let collection = read_collection(path)?;
+	let mut imported = ImportedCollection {
+		name: collection.name.clone(),
+		modules: Vec::new(),
+		missing: Vec::new(),
+	};
+	let count = collection.mods.len().max(collection.mod_names.len());
+	for index in 0..count {
+		let registry = collection.mods.get(index);
+		let name = collection.mod_names.get(index);
+		let by_registry = registry.and_then(|registry| {
+			let normalized = registry.replace('\\', "/").to_lowercase();
+			manager
+				.modules()
+				.find(|module| {
+					registry_id(module).is_some_and(|id| id.to_lowercase() == normalized)
+				})
+				.cloned()
+		});
+		let module = by_registry.or_else(|| name.and_then(|name| manager.find_module(name)));
+		match module {
+			Some(module) => imported.modules.push(module.meta.name.clone()),
+			None => imported.missing.extend(name.or(registry).cloned()),
+		}
+	}
+	Ok(imported)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::path::PathBuf;
+
+	fn resources() -> PathBuf {
+		Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/resources")
+	}
+
+	#[test]
+	fn test_read_collection() {
+		let collection = read_collection(&resources().join("irony/collection.zip")).unwrap();
+		assert_eq!(collection.name, "Multiplayer");
+		assert_eq!(collection.game.as_deref(), Some("EuropaUniversalisIV"));
+		assert_eq!(collection.mods.len(), 3);
+		assert_eq!(collection.mod_names[2], "Gamma");
+	}
+
+	#[test]
+	fn test_import_collection() {
+		let mut manager = ModuleManager::new();
+		manager.load_mod_dir(resources().join("mods"));
+		let imported =
+			import_collection(&resources().join("irony/collection.zip"), &manager).unwrap();
+		assert_eq!(imported.name, "Multiplayer");
+		assert_eq!(imported.modules, vec!["Beta", "Alpha"]);
+		assert_eq!(imported.missing, vec!["Gamma"]);
+		assert!(import_collection(&resources().join("dlc_load.json"), &manager).is_err());
+	}
+}
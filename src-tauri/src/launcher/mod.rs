@@ -0,0 +1,3 @@
+pub mod database;
+pub mod dlc_load;
+pub mod irony;
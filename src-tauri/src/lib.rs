@@ -1,21 +1,38 @@
+mod analysis;
 mod config;
 mod lang;
+mod launcher;
+mod merge;
 mod module;
+mod share;
+mod steam;
 mod utility;
+mod vfs;
 pub mod manager;
 
 use dashmap::DashMap;
 use log::{debug, error, info};
 use serde_json;
 use serde_yaml;
-use std::sync::LazyLock;
+use std::sync::{LazyLock, Mutex};
 use tauri::RunEvent;
 
 static GAME_CONFIG: LazyLock<DashMap<String, config::game::GameConfig>> =
 	LazyLock::new(|| DashMap::new());
 
+static MERGE_SESSIONS: LazyLock<DashMap<String, merge::session::MergeSession>> =
+	LazyLock::new(|| DashMap::new());
+
 static CONFIG: LazyLock<config::base::Config> = LazyLock::new(|| config::base::Config::default());
 
+static PLAYSETS: LazyLock<Mutex<config::playset::PlaysetStore>> = LazyLock::new(|| {
+	let store = config::playset::PlaysetStore::load(&CONFIG.playset_save_path).unwrap_or_else(|e| {
+		error!("{}", e);
+		config::playset::PlaysetStore::default()
+	});
+	Mutex::new(store)
+});
+
 fn load_game_config() {
 	if CONFIG.config_save_path.exists() {
 		info!("Loading config from {:?}", CONFIG.config_save_path);
@@ -100,6 +117,20 @@ async fn setup_game_config(
 		.map_err(|e| format!("Error setting up game config: {}", e))?
 }
 
+/// 从 Steam 库中检测已安装的 Paradox 游戏，返回可直接用于 setup_game_config 的配置
+#[tauri::command(rename_all = "snake_case")]
+async fn detect_game_configs() -> Result<serde_json::Value, String> {
+	log::info!("detect_game_configs");
+	let home = std::env::var_os("HOME").ok_or("HOME is not set")?;
+	tokio::task::spawn_blocking(move || {
+		let home = std::path::PathBuf::from(home);
+		let roots = steam::detect::steam_roots(&home);
+		serde_json::to_value(steam::detect::detect_games(&home, &roots)).map_err(|e| e.to_string())
+	})
+		.await
+		.map_err(|e| format!("Error detecting games: {}", e))?
+}
+
 #[tauri::command(rename_all = "snake_case")]
 async fn read_game_config(game_name: &str) -> Result<serde_json::Value, String> {
 	log::info!("read_game_config: game_name: {}", game_name);
@@ -114,6 +145,756 @@ async fn read_game_config(game_name: &str) -> Result<serde_json::Value, String>
 		.map_err(|e| format!("Error reading game config: {}", e))?
 }
 
+fn get_game_config(game_name: &str) -> Result<config::game::GameConfig, String> {
+	GAME_CONFIG
+		.get(game_name)
+		.map(|entry| entry.value().clone())
+		.ok_or_else(|| format!("Game config {} not found", game_name))
+}
+
+/// 内置规则表叠加用户规则文件后，某个游戏的目录规则
+fn get_game_rules(game_name: &str) -> analysis::rules::GameRules {
+	analysis::rules::RuleTable::load(&CONFIG.override_rules_path).for_game(game_name)
+}
+
+/// 在后台线程中加载游戏的全部 Mod，并将按给定顺序排列的 Mod 交给分析函数
+async fn analyze_modules<T, F>(
+	game_name: String,
+	mod_names: Vec<String>,
+	analyze: F,
+) -> Result<serde_json::Value, String>
+where
+	T: serde::Serialize,
+	F: FnOnce(&config::game::GameConfig, &[&module::module::Module]) -> Result<T, String>
+		+ Send
+		+ 'static,
+{
+	let game_config = get_game_config(&game_name)?;
+	tokio::task::spawn_blocking(move || {
+		let manager = manager::ModuleManager::from_game_config(&game_config);
+		let modules = manager.modules_in_order(&mod_names)?;
+		let modules: Vec<&module::module::Module> = modules.iter().map(|m| m.as_ref()).collect();
+		serde_json::to_value(analyze(&game_config, &modules)?).map_err(|e| e.to_string())
+	})
+		.await
+		.map_err(|e| format!("Error analyzing modules: {}", e))?
+}
+
+#[tauri::command(rename_all = "snake_case")]
+async fn analyze_file_conflicts(
+	game_name: String,
+	mod_names: Vec<String>,
+) -> Result<serde_json::Value, String> {
+	log::info!(
+		"analyze_file_conflicts: game_name: {}, mod_names: {:?}",
+		game_name,
+		mod_names
+	);
+	analyze_modules(game_name, mod_names, |_, modules| {
+		Ok(analysis::file_conflict::find_file_conflicts(modules))
+	})
+		.await
+}
+
+#[tauri::command(rename_all = "snake_case")]
+async fn analyze_object_conflicts(
+	game_name: String,
+	mod_names: Vec<String>,
+) -> Result<serde_json::Value, String> {
+	log::info!(
+		"analyze_object_conflicts: game_name: {}, mod_names: {:?}",
+		game_name,
+		mod_names
+	);
+	analyze_modules(game_name, mod_names, |game_config, modules| {
+		let rules = get_game_rules(&game_config.game_name);
+		Ok(analysis::object_conflict::find_object_conflicts(modules, &rules))
+	})
+		.await
+}
+
+#[tauri::command(rename_all = "snake_case")]
+async fn analyze_localisation_conflicts(
+	game_name: String,
+	mod_names: Vec<String>,
+) -> Result<serde_json::Value, String> {
+	log::info!(
+		"analyze_localisation_conflicts: game_name: {}, mod_names: {:?}",
+		game_name,
+		mod_names
+	);
+	analyze_modules(game_name, mod_names, |game_config, modules| {
+		let rules = get_game_rules(&game_config.game_name);
+		Ok(analysis::localisation::find_localisation_conflicts(modules, &rules))
+	})
+		.await
+}
+
+#[tauri::command(rename_all = "snake_case")]
+async fn analyze_missing_localisation(
+	game_name: String,
+	mod_names: Vec<String>,
+	languages: Vec<String>,
+) -> Result<serde_json::Value, String> {
+	log::info!(
+		"analyze_missing_localisation: game_name: {}, mod_names: {:?}, languages: {:?}",
+		game_name,
+		mod_names,
+		languages
+	);
+	analyze_modules(game_name, mod_names, move |game_config, modules| {
+		let vanilla = vfs::effective::load_vanilla(game_config)?;
+		Ok(analysis::missing_localisation::find_missing_localisation(
+			&vanilla, modules, &languages,
+		))
+	})
+		.await
+}
+
+#[tauri::command(rename_all = "snake_case")]
+async fn analyze_event_collisions(
+	game_name: String,
+	mod_names: Vec<String>,
+) -> Result<serde_json::Value, String> {
+	log::info!(
+		"analyze_event_collisions: game_name: {}, mod_names: {:?}",
+		game_name,
+		mod_names
+	);
+	analyze_modules(game_name, mod_names, |game_config, modules| {
+		let rules = get_game_rules(&game_config.game_name);
+		Ok(analysis::event::find_event_collisions(modules, &rules))
+	})
+		.await
+}
+
+#[tauri::command(rename_all = "snake_case")]
+async fn analyze_interface_conflicts(
+	game_name: String,
+	mod_names: Vec<String>,
+) -> Result<serde_json::Value, String> {
+	log::info!(
+		"analyze_interface_conflicts: game_name: {}, mod_names: {:?}",
+		game_name,
+		mod_names
+	);
+	analyze_modules(game_name, mod_names, |game_config, modules| {
+		let vanilla = vfs::effective::load_vanilla(game_config)?;
+		let rules = get_game_rules(&game_config.game_name);
+		Ok(analysis::interface::find_interface_conflicts(
+			&vanilla, modules, &rules,
+		))
+	})
+		.await
+}
+
+#[tauri::command(rename_all = "snake_case")]
+async fn analyze_replaced_files(
+	game_name: String,
+	mod_names: Vec<String>,
+) -> Result<serde_json::Value, String> {
+	log::info!(
+		"analyze_replaced_files: game_name: {}, mod_names: {:?}",
+		game_name,
+		mod_names
+	);
+	analyze_modules(game_name, mod_names, |game_config, modules| {
+		let vanilla = vfs::effective::load_vanilla(game_config)?;
+		Ok(vfs::effective::find_hidden_files(&vanilla, modules))
+	})
+		.await
+}
+
+#[tauri::command(rename_all = "snake_case")]
+async fn resolve_virtual_tree(
+	game_name: String,
+	mod_names: Vec<String>,
+) -> Result<serde_json::Value, String> {
+	log::info!(
+		"resolve_virtual_tree: game_name: {}, mod_names: {:?}",
+		game_name,
+		mod_names
+	);
+	analyze_modules(game_name, mod_names, |game_config, modules| {
+		vfs::tree::VirtualTree::resolve(game_config, modules)
+	})
+		.await
+}
+
+#[tauri::command(rename_all = "snake_case")]
+async fn resolve_file_provenance(
+	game_name: String,
+	mod_names: Vec<String>,
+	relative_path: String,
+) -> Result<serde_json::Value, String> {
+	log::info!(
+		"resolve_file_provenance: game_name: {}, relative_path: {}",
+		game_name,
+		relative_path
+	);
+	analyze_modules(game_name, mod_names, move |game_config, modules| {
+		let tree = vfs::tree::VirtualTree::resolve(game_config, modules)?;
+		tree.find(&relative_path)
+			.cloned()
+			.ok_or_else(|| format!("File {} is not loaded by the game", relative_path))
+	})
+		.await
+}
+
+#[tauri::command(rename_all = "snake_case")]
+async fn merge_script_file(
+	game_name: String,
+	mod_names: Vec<String>,
+	relative_path: String,
+) -> Result<serde_json::Value, String> {
+	log::info!(
+		"merge_script_file: game_name: {}, mod_names: {:?}, relative_path: {}",
+		game_name,
+		mod_names,
+		relative_path
+	);
+	analyze_modules(game_name, mod_names, move |game_config, modules| {
+		let vanilla = vfs::effective::load_vanilla(game_config)?;
+		let merged = merge::three_way::merge_file(&vanilla, modules, &relative_path)?;
+		Ok(merged.report())
+	})
+		.await
+}
+
+#[tauri::command(rename_all = "snake_case")]
+async fn generate_compatibility_patch(
+	game_name: String,
+	mod_names: Vec<String>,
+	patch_name: String,
+	files: Vec<merge::patch::PatchFile>,
+) -> Result<serde_json::Value, String> {
+	log::info!(
+		"generate_compatibility_patch: game_name: {}, mod_names: {:?}, patch_name: {}",
+		game_name,
+		mod_names,
+		patch_name
+	);
+	let game_config = get_game_config(&game_name)?;
+	tokio::task::spawn_blocking(move || {
+		let mut manager = manager::ModuleManager::from_game_config(&game_config);
+		manager.modules_in_order(&mod_names)?;
+		let patch = merge::patch::generate_patch(
+			&mut manager,
+			&game_config.mod_dir,
+			&patch_name,
+			&mod_names,
+			&files,
+		)?;
+		serde_json::to_value(patch).map_err(|e| e.to_string())
+	})
+		.await
+		.map_err(|e| format!("Error generating compatibility patch: {}", e))?
+}
+
+#[tauri::command(rename_all = "snake_case")]
+async fn start_merge_session(
+	game_name: String,
+	mod_names: Vec<String>,
+	relative_path: String,
+) -> Result<serde_json::Value, String> {
+	log::info!(
+		"start_merge_session: game_name: {}, mod_names: {:?}, relative_path: {}",
+		game_name,
+		mod_names,
+		relative_path
+	);
+	let session_id = merge::session::session_id(&game_name, &mod_names, &relative_path);
+	// 已有保存的会话时直接继续
+	if MERGE_SESSIONS.contains_key(&session_id)
+		|| CONFIG
+			.merge_session_dir
+			.join(format!("{}.json", session_id))
+			.exists()
+	{
+		return update_merge_session(session_id, |_| Ok(())).await;
+	}
+	let names = mod_names.clone();
+	analyze_modules(game_name, mod_names, move |game_config, modules| {
+		let vanilla = vfs::effective::load_vanilla(game_config)?;
+		let merged = merge::three_way::merge_file(&vanilla, modules, &relative_path)?;
+		let session =
+			merge::session::MergeSession::new(game_config.game_name.clone(), names, merged);
+		session.save(&CONFIG.merge_session_dir)?;
+		let state = session.state();
+		MERGE_SESSIONS.insert(session.id.clone(), session);
+		Ok(state)
+	})
+		.await
+}
+
+/// 取出内存中或磁盘上保存的合并会话，修改后保存并返回最新状态
+async fn update_merge_session<F>(session_id: String, update: F) -> Result<serde_json::Value, String>
+where
+	F: FnOnce(&mut merge::session::MergeSession) -> Result<(), String> + Send + 'static,
+{
+	tokio::task::spawn_blocking(move || {
+		let mut session = match MERGE_SESSIONS.entry(session_id.clone()) {
+			dashmap::Entry::Occupied(entry) => entry.into_ref(),
+			dashmap::Entry::Vacant(entry) => entry.insert(merge::session::MergeSession::load(
+				&CONFIG.merge_session_dir,
+				&session_id,
+			)?),
+		};
+		update(&mut session)?;
+		session.save(&CONFIG.merge_session_dir)?;
+		serde_json::to_value(session.state()).map_err(|e| e.to_string())
+	})
+		.await
+		.map_err(|e| format!("Error updating merge session: {}", e))?
+}
+
+#[tauri::command(rename_all = "snake_case")]
+async fn get_merge_session(session_id: String) -> Result<serde_json::Value, String> {
+	log::info!("get_merge_session: session_id: {}", session_id);
+	update_merge_session(session_id, |_| Ok(())).await
+}
+
+#[tauri::command(rename_all = "snake_case")]
+async fn resolve_merge_hunk(
+	session_id: String,
+	hunk: usize,
+	resolution: Option<merge::session::Resolution>,
+) -> Result<serde_json::Value, String> {
+	log::info!(
+		"resolve_merge_hunk: session_id: {}, hunk: {}, resolution: {:?}",
+		session_id,
+		hunk,
+		resolution
+	);
+	update_merge_session(session_id, move |session| session.resolve(hunk, resolution)).await
+}
+
+#[tauri::command(rename_all = "snake_case")]
+async fn undo_merge_session(session_id: String) -> Result<serde_json::Value, String> {
+	log::info!("undo_merge_session: session_id: {}", session_id);
+	update_merge_session(session_id, |session| {
+		session.undo();
+		Ok(())
+	})
+		.await
+}
+
+#[tauri::command(rename_all = "snake_case")]
+async fn redo_merge_session(session_id: String) -> Result<serde_json::Value, String> {
+	log::info!("redo_merge_session: session_id: {}", session_id);
+	update_merge_session(session_id, |session| {
+		session.redo();
+		Ok(())
+	})
+		.await
+}
+
+#[tauri::command(rename_all = "snake_case")]
+async fn delete_merge_session(session_id: String) -> Result<(), String> {
+	log::info!("delete_merge_session: session_id: {}", session_id);
+	MERGE_SESSIONS.remove(&session_id);
+	tokio::task::spawn_blocking(move || {
+		merge::session::MergeSession::delete(&CONFIG.merge_session_dir, &session_id)
+	})
+		.await
+		.map_err(|e| format!("Error deleting merge session: {}", e))?
+}
+
+#[tauri::command(rename_all = "snake_case")]
+async fn read_launcher_playsets(game_name: String) -> Result<serde_json::Value, String> {
+	log::info!("read_launcher_playsets: game_name: {}", game_name);
+	let game_config = get_game_config(&game_name)?;
+	tokio::task::spawn_blocking(move || {
+		let mut playsets = launcher::database::read_playsets(&game_config.launcher_db_path())?;
+		let manager = manager::ModuleManager::from_game_config(&game_config);
+		launcher::database::match_modules(&mut playsets, &manager);
+		serde_json::to_value(playsets).map_err(|e| e.to_string())
+	})
+		.await
+		.map_err(|e| format!("Error reading launcher playsets: {}", e))?
+}
+
+#[tauri::command(rename_all = "snake_case")]
+async fn write_launcher_playset(
+	game_name: String,
+	playset_id: String,
+	mods: Vec<launcher::database::PlaysetModUpdate>,
+) -> Result<serde_json::Value, String> {
+	log::info!(
+		"write_launcher_playset: game_name: {}, playset_id: {}, mods: {:?}",
+		game_name,
+		playset_id,
+		mods
+	);
+	let game_config = get_game_config(&game_name)?;
+	tokio::task::spawn_blocking(move || {
+		let backup_path = launcher::database::write_playset(
+			&game_config.launcher_db_path(),
+			&playset_id,
+			&mods,
+		)?;
+		serde_json::to_value(backup_path).map_err(|e| e.to_string())
+	})
+		.await
+		.map_err(|e| format!("Error writing launcher playset: {}", e))?
+}
+
+#[tauri::command(rename_all = "snake_case")]
+async fn write_dlc_load(
+	game_name: String,
+	mod_names: Vec<String>,
+	disabled_dlcs: Option<Vec<String>>,
+) -> Result<serde_json::Value, String> {
+	log::info!(
+		"write_dlc_load: game_name: {}, mod_names: {:?}, disabled_dlcs: {:?}",
+		game_name,
+		mod_names,
+		disabled_dlcs
+	);
+	analyze_modules(game_name, mod_names, move |game_config, modules| {
+		launcher::dlc_load::write_dlc_load(&game_config.user_dir(), modules, disabled_dlcs)
+	})
+		.await
+}
+
+/// 导入 dlc_load.json，给出 `playset_name` 时以导入的加载顺序新建播放集
+#[tauri::command(rename_all = "snake_case")]
+async fn import_dlc_load(
+	game_name: String,
+	playset_name: Option<String>,
+) -> Result<serde_json::Value, String> {
+	log::info!(
+		"import_dlc_load: game_name: {}, playset_name: {:?}",
+		game_name,
+		playset_name
+	);
+	let game_config = get_game_config(&game_name)?;
+	tokio::task::spawn_blocking(move || {
+		let manager = manager::ModuleManager::from_game_config(&game_config);
+		let imported = launcher::dlc_load::import_dlc_load(&game_config.user_dir(), &manager)?;
+		if let Some(playset_name) = playset_name {
+			let entries = imported
+				.modules
+				.iter()
+				.map(|module| config::playset::PlaysetEntry::new(module.clone()))
+				.collect();
+			update_playsets(|store| {
+				store
+					.create(&game_config.game_name, &playset_name, entries)
+					.cloned()
+			})?;
+		}
+		serde_json::to_value(imported).map_err(|e| e.to_string())
+	})
+		.await
+		.map_err(|e| format!("Error importing dlc_load.json: {}", e))?
+}
+
+/// 修改播放集后立即保存到磁盘，修改或保存失败时内存中的播放集保持不变
+fn update_playsets<T, F>(update: F) -> Result<serde_json::Value, String>
+where
+	T: serde::Serialize,
+	F: FnOnce(&mut config::playset::PlaysetStore) -> Result<T, String>,
+{
+	let mut store = PLAYSETS.lock().map_err(|e| e.to_string())?;
+	let mut updated = store.clone();
+	let value = serde_json::to_value(update(&mut updated)?).map_err(|e| e.to_string())?;
+	updated.save(&CONFIG.playset_save_path)?;
+	*store = updated;
+	Ok(value)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+async fn list_playsets(game_name: String) -> Result<serde_json::Value, String> {
+	log::info!("list_playsets: game_name: {}", game_name);
+	let store = PLAYSETS.lock().map_err(|e| e.to_string())?;
+	serde_json::to_value(store.list(&game_name)).map_err(|e| e.to_string())
+}
+
+#[tauri::command(rename_all = "snake_case")]
+async fn create_playset(
+	game_name: String,
+	name: String,
+	entries: Option<Vec<config::playset::PlaysetEntry>>,
+) -> Result<serde_json::Value, String> {
+	log::info!("create_playset: game_name: {}, name: {}", game_name, name);
+	update_playsets(|store| {
+		store
+			.create(&game_name, &name, entries.unwrap_or_default())
+			.cloned()
+	})
+}
+
+#[tauri::command(rename_all = "snake_case")]
+async fn clone_playset(
+	game_name: String,
+	name: String,
+	new_name: String,
+) -> Result<serde_json::Value, String> {
+	log::info!(
+		"clone_playset: game_name: {}, name: {}, new_name: {}",
+		game_name,
+		name,
+		new_name
+	);
+	update_playsets(|store| store.clone_playset(&game_name, &name, &new_name).cloned())
+}
+
+#[tauri::command(rename_all = "snake_case")]
+async fn rename_playset(
+	game_name: String,
+	name: String,
+	new_name: String,
+) -> Result<serde_json::Value, String> {
+	log::info!(
+		"rename_playset: game_name: {}, name: {}, new_name: {}",
+		game_name,
+		name,
+		new_name
+	);
+	update_playsets(|store| store.rename(&game_name, &name, &new_name).cloned())
+}
+
+#[tauri::command(rename_all = "snake_case")]
+async fn reorder_playset(
+	game_name: String,
+	name: String,
+	order: Vec<String>,
+) -> Result<serde_json::Value, String> {
+	log::info!(
+		"reorder_playset: game_name: {}, name: {}, order: {:?}",
+		game_name,
+		name,
+		order
+	);
+	update_playsets(|store| store.reorder(&game_name, &name, &order).cloned())
+}
+
+#[tauri::command(rename_all = "snake_case")]
+async fn update_playset(
+	game_name: String,
+	name: String,
+	entries: Vec<config::playset::PlaysetEntry>,
+	notes: Option<String>,
+) -> Result<serde_json::Value, String> {
+	log::info!("update_playset: game_name: {}, name: {}", game_name, name);
+	update_playsets(|store| store.update(&game_name, &name, entries, notes).cloned())
+}
+
+#[tauri::command(rename_all = "snake_case")]
+async fn delete_playset(game_name: String, name: String) -> Result<serde_json::Value, String> {
+	log::info!("delete_playset: game_name: {}, name: {}", game_name, name);
+	update_playsets(|store| store.delete(&game_name, &name))
+}
+
+/// 将播放集中已启用的 Mod 写入 dlc_load.json，游戏启动时即按该顺序加载
+#[tauri::command(rename_all = "snake_case")]
+async fn apply_playset(game_name: String, name: String) -> Result<serde_json::Value, String> {
+	log::info!("apply_playset: game_name: {}, name: {}", game_name, name);
+	let mod_names = PLAYSETS
+		.lock()
+		.map_err(|e| e.to_string())?
+		.get(&game_name, &name)?
+		.enabled_modules();
+	analyze_modules(game_name, mod_names, |game_config, modules| {
+		launcher::dlc_load::write_dlc_load(&game_config.user_dir(), modules, None)
+	})
+		.await
+}
+
+/// 导出播放集，`file_path` 不为空时同时写出 JSON 文件，返回的分享字符串可直接发给其他玩家
+#[tauri::command(rename_all = "snake_case")]
+async fn export_playset(
+	game_name: String,
+	name: String,
+	file_path: Option<String>,
+) -> Result<serde_json::Value, String> {
+	log::info!(
+		"export_playset: game_name: {}, name: {}, file_path: {:?}",
+		game_name,
+		name,
+		file_path
+	);
+	let mod_names = PLAYSETS
+		.lock()
+		.map_err(|e| e.to_string())?
+		.get(&game_name, &name)?
+		.enabled_modules();
+	analyze_modules(game_name.clone(), mod_names, move |_, modules| {
+		let playset = share::playset::SharedPlayset::from_modules(&game_name, &name, modules);
+		if let Some(file_path) = file_path {
+			std::fs::write(&file_path, playset.to_json()?)
+				.map_err(|e| format!("Error writing {}: {}", file_path, e))?;
+		}
+		let share_string = playset.to_share_string()?;
+		Ok(serde_json::json!({ "playset": playset, "share_string": share_string }))
+	})
+		.await
+}
+
+/// 导入导出的 JSON 文件或分享字符串，`playset_name` 不为空时以找到的本地 Mod 创建播放集
+#[tauri::command(rename_all = "snake_case")]
+async fn import_shared_playset(
+	game_name: String,
+	content: String,
+	playset_name: Option<String>,
+) -> Result<serde_json::Value, String> {
+	log::info!(
+		"import_shared_playset: game_name: {}, playset_name: {:?}",
+		game_name,
+		playset_name
+	);
+	let shared = share::playset::SharedPlayset::parse(&content)?;
+	if shared.game_name != game_name {
+		return Err(format!("Playset is for {}", shared.game_name));
+	}
+	let game_config = get_game_config(&game_name)?;
+	tokio::task::spawn_blocking(move || {
+		let manager = manager::ModuleManager::from_game_config(&game_config);
+		let imported = shared.match_local(&manager);
+		if let Some(playset_name) = playset_name {
+			let entries = imported
+				.modules
+				.iter()
+				.map(|module| config::playset::PlaysetEntry::new(module.clone()))
+				.collect();
+			update_playsets(|store| {
+				store
+					.create(&game_config.game_name, &playset_name, entries)
+					.cloned()
+			})?;
+		}
+		serde_json::to_value(imported).map_err(|e| e.to_string())
+	})
+		.await
+		.map_err(|e| format!("Error importing playset: {}", e))?
+}
+
+/// 计算按给定顺序排列的 Mod 的联机校验指纹
+#[tauri::command(rename_all = "snake_case")]
+async fn playset_fingerprint(
+	game_name: String,
+	mod_names: Vec<String>,
+) -> Result<serde_json::Value, String> {
+	log::info!(
+		"playset_fingerprint: game_name: {}, mod_names: {:?}",
+		game_name,
+		mod_names
+	);
+	analyze_modules(game_name.clone(), mod_names, move |_, modules| {
+		Ok(share::checksum::PlaysetFingerprint::from_modules(
+			&game_name, modules,
+		))
+	})
+		.await
+}
+
+/// 比较两份导出的指纹，找出内容不同的 Mod 与文件
+#[tauri::command(rename_all = "snake_case")]
+async fn compare_fingerprints(ours: String, theirs: String) -> Result<serde_json::Value, String> {
+	log::info!("compare_fingerprints");
+	let ours = share::checksum::PlaysetFingerprint::parse(&ours)?;
+	let theirs = share::checksum::PlaysetFingerprint::parse(&theirs)?;
+	serde_json::to_value(ours.compare(&theirs)).map_err(|e| e.to_string())
+}
+
+/// 导入 Irony Mod Manager 导出的合集并创建播放集，`playset_name` 为空时沿用合集名称
+#[tauri::command(rename_all = "snake_case")]
+async fn import_irony_collection(
+	game_name: String,
+	file_path: String,
+	playset_name: Option<String>,
+) -> Result<serde_json::Value, String> {
+	log::info!(
+		"import_irony_collection: game_name: {}, file_path: {}, playset_name: {:?}",
+		game_name,
+		file_path,
+		playset_name
+	);
+	let game_config = get_game_config(&game_name)?;
+	tokio::task::spawn_blocking(move || {
+		let manager = manager::ModuleManager::from_game_config(&game_config);
+		let imported =
+			launcher::irony::import_collection(std::path::Path::new(&file_path), &manager)?;
+		let entries = imported
+			.modules
+			.iter()
+			.map(|module| config::playset::PlaysetEntry::new(module.clone()))
+			.collect();
+		let playset_name = playset_name.unwrap_or_else(|| imported.name.clone());
+		update_playsets(|store| {
+			store
+				.create(&game_config.game_name, &playset_name, entries)
+				.cloned()
+		})?;
+		serde_json::to_value(imported).map_err(|e| e.to_string())
+	})
+		.await
+		.map_err(|e| format!("Error importing Irony collection: {}", e))?
+}
+
+/// 列出游戏所在 Steam 库中的创意工坊物品及其订阅状态
+#[tauri::command(rename_all = "snake_case")]
+async fn scan_workshop_items(game_name: String) -> Result<serde_json::Value, String> {
+	log::info!("scan_workshop_items: game_name: {}", game_name);
+	let game_config = get_game_config(&game_name)?;
+	tokio::task::spawn_blocking(move || {
+		let location = steam::workshop::WorkshopLocation::from_game_config(&game_config)
+			.ok_or_else(|| format!("{} is not installed in a Steam library", game_name))?;
+		let manager = manager::ModuleManager::from_game_config(&game_config);
+		serde_json::to_value(location.scan(&manager)?).map_err(|e| e.to_string())
+	})
+		.await
+		.map_err(|e| format!("Error scanning Workshop items: {}", e))?
+}
+
+/// 查找同一个 Mod 的重复安装
+#[tauri::command(rename_all = "snake_case")]
+async fn find_duplicate_mods(game_name: String) -> Result<serde_json::Value, String> {
+	log::info!("find_duplicate_mods: game_name: {}", game_name);
+	let game_config = get_game_config(&game_name)?;
+	tokio::task::spawn_blocking(move || {
+		serde_json::to_value(duplicate_groups(&game_config)).map_err(|e| e.to_string())
+	})
+		.await
+		.map_err(|e| format!("Error finding duplicate mods: {}", e))?
+}
+
+fn duplicate_groups(
+	game_config: &config::game::GameConfig,
+) -> Vec<analysis::duplicate::DuplicateGroup> {
+	let installations = manager::ModuleManager::installations(game_config);
+	let manager = manager::ModuleManager::from_game_config(game_config);
+	analysis::duplicate::find_duplicates(&installations, &manager)
+}
+
+/// 停用或删除多余安装的外层 .mod 文件，返回停用后的文件路径
+#[tauri::command(rename_all = "snake_case")]
+async fn resolve_duplicate_mod(
+	game_name: String,
+	mod_file: String,
+	action: analysis::duplicate::DuplicateAction,
+) -> Result<serde_json::Value, String> {
+	log::info!(
+		"resolve_duplicate_mod: game_name: {}, mod_file: {}, action: {:?}",
+		game_name,
+		mod_file,
+		action
+	);
+	let game_config = get_game_config(&game_name)?;
+	tokio::task::spawn_blocking(move || {
+		let groups = duplicate_groups(&game_config);
+		let path = analysis::duplicate::resolve_duplicate(
+			&groups,
+			std::path::Path::new(&mod_file),
+			action,
+		)?;
+		serde_json::to_value(path).map_err(|e| e.to_string())
+	})
+		.await
+		.map_err(|e| format!("Error resolving duplicate mod: {}", e))?
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
 	let app = tauri::Builder::default()
@@ -124,7 +905,45 @@ pub fn run() {
 		.plugin(tauri_plugin_log::Builder::new().build())
 		.invoke_handler(tauri::generate_handler![
 			setup_game_config,
-			read_game_config
+			read_game_config,
+			analyze_file_conflicts,
+			analyze_object_conflicts,
+			analyze_localisation_conflicts,
+			analyze_missing_localisation,
+			analyze_event_collisions,
+			analyze_interface_conflicts,
+			analyze_replaced_files,
+			resolve_virtual_tree,
+			resolve_file_provenance,
+			merge_script_file,
+			generate_compatibility_patch,
+			start_merge_session,
+			get_merge_session,
+			resolve_merge_hunk,
+			undo_merge_session,
+			redo_merge_session,
+			delete_merge_session,
+			read_launcher_playsets,
+			write_launcher_playset,
+			write_dlc_load,
+			import_dlc_load,
+			list_playsets,
+			create_playset,
+			clone_playset,
+			rename_playset,
+			reorder_playset,
+			update_playset,
+			delete_playset,
+			apply_playset,
+			export_playset,
+			import_shared_playset,
+			playset_fingerprint,
+			compare_fingerprints,
+			import_irony_collection,
+			detect_game_configs,
+			scan_workshop_items,
+			find_duplicate_mods,
+			resolve_duplicate_mod
 		])
 		.setup(|_app| {
 			load_game_config();
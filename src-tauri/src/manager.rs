@@ -1,14 +1,23 @@
+use crate::config::game::GameConfig;
 use crate::module::component::Component;
 use crate::module::dependency::DependencyBuilder;
 use crate::module::meta::Meta;
 use crate::module::module::Module;
+use crate::steam::workshop::WorkshopLocation;
+use crate::utility::get_mods_metafiles;
 use std::collections::HashMap;
 use std::path::Path;
 use std::rc::Rc;
-struct ModuleManager {
+pub struct ModuleManager {
 	modules: HashMap<String, Rc<Module>>,
 }
 
+impl Default for ModuleManager {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
 impl ModuleManager {
 	pub fn new() -> ModuleManager {
 		ModuleManager {
@@ -16,6 +25,16 @@ impl ModuleManager {
 		}
 	}
 
+	/// 加载某个游戏 mod 目录下的全部 Mod
+	pub(crate) fn from_game_config(game_config: &GameConfig) -> ModuleManager {
+		let mut manager = ModuleManager::new();
+		manager.load_mod_dir(&game_config.mod_dir);
+		if let Some(location) = WorkshopLocation::from_game_config(game_config) {
+			manager.load_workshop_dir(&location);
+		}
+		manager
+	}
+
 	pub fn add_module(&mut self, module: Module) {
 		self.modules
 			.insert(module.meta.name.clone(), Rc::new(module));
@@ -25,6 +44,107 @@ impl ModuleManager {
 		self.modules.get(name).cloned()
 	}
 
+	/// 管理器中的全部 Mod，顺序不固定
+	pub fn modules(&self) -> impl Iterator<Item = &Rc<Module>> {
+		self.modules.values()
+	}
+
+	/// 按名称依次取出 Mod，顺序即加载顺序
+	pub fn modules_in_order(&self, names: &[String]) -> Result<Vec<Rc<Module>>, String> {
+		names
+			.iter()
+			.map(|name| {
+				self.find_module(name)
+					.ok_or_else(|| format!("Module {} not found", name))
+			})
+			.collect()
+	}
+
+	/// 读取 mod 目录下所有的外层 .mod 文件并加入管理器
+	pub fn load_mod_dir<P: AsRef<Path>>(&mut self, mod_dir: P) {
+		for file in get_mods_metafiles(&mod_dir) {
+			// Mod 文件夹内部的 descriptor.mod 由外层 .mod 文件指向，不单独加载
+			if file.file_name().unwrap_or_default() == "descriptor.mod" {
+				continue;
+			}
+			match self.create_from_mod_file(&file) {
+				Ok(module) => self.add_module(module),
+				Err(e) => eprintln!("Error loading mod file {}: {}", file.display(), e),
+			}
+		}
+	}
+
+	/// 读取创意工坊内容目录中尚未通过外层 .mod 文件加载的 Mod
+	pub(crate) fn load_workshop_dir(&mut self, location: &WorkshopLocation) {
+		for (id, dir) in location.item_dirs() {
+			let loaded = self
+				.modules()
+				.any(|module| module.meta.remote_file_id.as_deref() == Some(id.as_str()));
+			if loaded {
+				continue;
+			}
+			let Some(module) = self.create_from_workshop_dir(id, &dir) else {
+				continue;
+			};
+			// 同名的本地 Mod 优先
+			if self.find_module(&module.meta.name).is_none() {
+				self.add_module(module);
+			}
+		}
+	}
+
+	/// 由创意工坊物品目录中的 descriptor.mod 创建 Mod
+	fn create_from_workshop_dir(&self, id: String, dir: &Path) -> Option<Module> {
+		let descriptor = dir.join("descriptor.mod");
+		if !descriptor.exists() {
+			return None;
+		}
+		let mut module = match self.create_from_mod_file(&descriptor) {
+			Ok(module) => module,
+			Err(e) => {
+				eprintln!("Error loading mod file {}: {}", descriptor.display(), e);
+				return None;
+			}
+		};
+		// 启动器尚未生成外层 .mod 文件，游戏还无法通过 dlc_load.json 加载该 Mod
+		module.meta.mod_file = None;
+		module.meta.remote_file_id.get_or_insert(id);
+		Some(module)
+	}
+
+	/// 游戏的全部 Mod 安装，不按名称去重，用于查找重复安装
+	///
+	/// 指向创意工坊物品目录的外层 .mod 文件与该目录视为同一个安装
+	pub(crate) fn installations(game_config: &GameConfig) -> Vec<Module> {
+		let manager = ModuleManager::new();
+		let mut installations: Vec<Module> = Vec::new();
+		for file in get_mods_metafiles(&game_config.mod_dir) {
+			if file.file_name().unwrap_or_default() == "descriptor.mod" {
+				continue;
+			}
+			match manager.create_from_mod_file(&file) {
+				Ok(module) => installations.push(module),
+				Err(e) => eprintln!("Error loading mod file {}: {}", file.display(), e),
+			}
+		}
+		let Some(location) = WorkshopLocation::from_game_config(game_config) else {
+			return installations;
+		};
+		for (id, dir) in location.item_dirs() {
+			let dir = dir.canonicalize().unwrap_or(dir);
+			let referenced = installations.iter().any(|module| {
+				Path::new(&module.meta.local_path)
+					.canonicalize()
+					.is_ok_and(|path| path == dir)
+			});
+			if referenced {
+				continue;
+			}
+			installations.extend(manager.create_from_workshop_dir(id, &dir));
+		}
+		installations
+	}
+
 	pub fn create_from_mod_file<P: AsRef<Path>>(
 		&self,
 		file_path: P,
@@ -32,7 +152,11 @@ impl ModuleManager {
 		let mut is_valid = true;
 		let meta = Meta::create_from_mod_file(file_path)?;
 
-		let components = match Component::get_components_from_dir(&meta.local_path) {
+		let components = match &meta.archive {
+			Some(archive) => Component::get_components_from_archive(archive),
+			None => Component::get_components_from_dir(&meta.local_path),
+		};
+		let components = match components {
 			Ok(components) => components,
 			Err(e) => {
 				eprintln!("Error getting components from directory: {}", e);
@@ -78,4 +202,41 @@ mod tests {
 		}
 		println!("{:?}", manager.modules);
 	}
+
+	#[test]
+	fn test_load_mod_dir() {
+		let mut manager = ModuleManager::new();
+		manager.load_mod_dir(Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/resources/mods"));
+		assert_eq!(manager.modules.len(), 2);
+
+		let ordered = manager
+			.modules_in_order(&["Beta".to_string(), "Alpha".to_string()])
+			.unwrap();
+		assert_eq!(ordered[0].meta.name, "Beta");
+		assert!(ordered[1].is_valid);
+		assert!(!ordered[1].components.is_empty());
+		assert!(manager.modules_in_order(&["Gamma".to_string()]).is_err());
+	}
+
+	#[test]
+	fn test_load_archived_mod() {
+		let resources = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/resources");
+		let mut manager = ModuleManager::new();
+		manager.load_mod_dir(resources.join("archive"));
+		let zeta = manager.find_module("Zeta").unwrap();
+		assert!(zeta.is_valid);
+
+		let mut alpha = ModuleManager::new();
+		alpha.load_mod_dir(resources.join("mods"));
+		let alpha = alpha.find_module("Alpha").unwrap();
+		assert_eq!(zeta.content_hash(), alpha.content_hash());
+
+		let events = zeta
+			.components
+			.iter()
+			.find(|c| c.relative_path() == "events/alpha_events.txt")
+			.unwrap();
+		let entries = crate::lang::script::parse_script(&events.read_text().unwrap()).unwrap();
+		assert!(!entries.is_empty());
+	}
 }
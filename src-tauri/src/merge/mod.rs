@@ -0,0 +1,3 @@
+pub mod patch;
+pub mod session;
+pub mod three_way;
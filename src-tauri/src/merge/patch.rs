@@ -0,0 +1,208 @@
+use crate::manager::ModuleManager;
+use crate::module::meta::MetaBuilder;
+use md5::{Digest, Md5};
+use serde::{Deserialize, Serialize};
+use std::path::{Component as PathComponent, Path, PathBuf};
+
+/// 兼容补丁中的一个文件，通常是解决冲突后的合并结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PatchFile {
+	pub relative_path: String,
+	pub content: String,
+}
+
+/// 生成的兼容补丁
+#[derive(Debug, Clone, Serialize)]
+pub struct GeneratedPatch {
+	pub name: String,
+	pub mod_file: PathBuf,       // 外层 .mod 文件
+	pub local_path: PathBuf,     // Mod 文件夹
+	pub load_order: Vec<String>, // 补丁位于最后的加载顺序
+}
+
+/// 由补丁名称得到文件夹名，保留各种文字的字母与数字，其余字符替换为下划线
+///
+/// 名称中没有可用的字符时（如全为符号），以名称的哈希作为文件夹名
+fn folder_name(name: &str) -> String {
+	let name = name.trim();
+	let folder: String = name
+		.chars()
+		.flat_map(|c| {
+			if c.is_alphanumeric() {
+				c.to_lowercase().collect::<Vec<char>>()
+			} else {
+				vec!['_']
+			}
+		})
+		.collect();
+	let folder = folder.trim_matches('_');
+	if !folder.is_empty() || name.is_empty() {
+		return folder.to_string();
+	}
+	let hash: String = Md5::digest(name.as_bytes())
+		.iter()
+		.take(4)
+		.map(|byte| format!("{:02x}", byte))
+		.collect();
+	format!("patch_{}", hash)
+}
+
+/// 补丁文件只能写入 Mod 文件夹内部
+fn patch_file_path(local_path: &Path, relative_path: &str) -> Result<PathBuf, String> {
+	let relative = Path::new(relative_path);
+	if relative_path.is_empty()
+		|| !relative
+			.components()
+			.all(|component| matches!(component, PathComponent::Normal(_)))
+	{
+		return Err(format!("Invalid patch file path {}", relative_path));
+	}
+	Ok(local_path.join(relative))
+}
+
+/// 在 mod 目录下创建兼容补丁 Mod，并将其加入管理器
+///
+/// 补丁依赖于 `load_order` 中的全部 Mod，因此会排在它们之后加载。
+/// 已存在同名文件夹或 .mod 文件时不会覆盖
+pub fn generate_patch(
+	manager: &mut ModuleManager,
+	mod_dir: &Path,
+	name: &str,
+	load_order: &[String],
+	files: &[PatchFile],
+) -> Result<GeneratedPatch, String> {
+	let folder = folder_name(name);
+	if folder.is_empty() {
+		return Err(format!("Invalid patch name {}", name));
+	}
+	if manager.find_module(name).is_some() {
+		return Err(format!("Module {} already exists", name));
+	}
+	let local_path = mod_dir.join(&folder);
+	let mod_file = mod_dir.join(format!("{}.mod", folder));
+	if local_path.exists() || mod_file.exists() {
+		return Err(format!("{} already exists", local_path.display()));
+	}
+
+	let meta = MetaBuilder::default()
+		.name(name.to_string())
+		.version(Some("1.0.0".to_string()))
+		.local_path(local_path.to_string_lossy().to_string())
+		.dependencies(load_order.to_vec())
+		.build()
+		.map_err(|e| e.to_string())?;
+
+	let write = |path: &Path, content: &str| {
+		if let Some(parent) = path.parent() {
+			std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+		}
+		std::fs::write(path, content)
+			.map_err(|e| format!("Error writing {}: {}", path.display(), e))
+	};
+	for file in files {
+		let path = patch_file_path(&local_path, &file.relative_path)?;
+		// 游戏只识别带 BOM 的 UTF-8 本地化文件
+		if file.relative_path.ends_with(".yml") && !file.content.starts_with('\u{feff}') {
+			write(&path, &format!("\u{feff}{}", file.content))?;
+		} else {
+			write(&path, &file.content)?;
+		}
+	}
+	write(&local_path.join("descriptor.mod"), &meta.to_mod_file(false))?;
+	write(&mod_file, &meta.to_mod_file(true))?;
+
+	let module = manager
+		.create_from_mod_file(&mod_file)
+		.map_err(|e| format!("Error loading generated patch: {}", e))?;
+	manager.add_module(module);
+
+	let mut load_order = load_order.to_vec();
+	load_order.push(name.to_string());
+	Ok(GeneratedPatch {
+		name: name.to_string(),
+		mod_file,
+		local_path,
+		load_order,
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_folder_name() {
+		assert_eq!(folder_name(" Alpha + Beta Patch "), "alpha___beta_patch");
+		assert_eq!(folder_name("瑞典 Patch"), "瑞典_patch");
+		assert_eq!(folder_name("Ёлка"), "ёлка");
+		let fallback = folder_name("!!");
+		assert!(fallback.starts_with("patch_") && fallback.len() == 14);
+		assert_eq!(folder_name("!!"), fallback);
+		assert_ne!(folder_name("??"), fallback);
+		assert_eq!(folder_name("  "), "");
+	}
+
+	#[test]
+	fn test_patch_file_path() {
+		let root = Path::new("mod/patch");
+		assert_eq!(
+			patch_file_path(root, "common/ideas/00_basic_ideas.txt").unwrap(),
+			root.join("common/ideas/00_basic_ideas.txt")
+		);
+		assert!(patch_file_path(root, "../alpha.mod").is_err());
+		assert!(patch_file_path(root, "/etc/passwd").is_err());
+	}
+
+	#[test]
+	fn test_generate_patch() {
+		let mod_dir = std::env::temp_dir().join(format!("pmm_patch_{}", std::process::id()));
+		std::fs::create_dir_all(&mod_dir).unwrap();
+		let mut manager = ModuleManager::new();
+		let load_order = vec!["Alpha".to_string(), "Beta".to_string()];
+		let files = vec![
+			PatchFile {
+				relative_path: "common/ideas/00_basic_ideas.txt".to_string(),
+				content: "innovativeness_ideas = {\n\tcategory = ADM\n}\n".to_string(),
+			},
+			PatchFile {
+				relative_path: "localisation/patch_l_english.yml".to_string(),
+				content: "l_english:\n shared_key:0 \"Patched\"\n".to_string(),
+			},
+		];
+		let patch = generate_patch(
+			&mut manager,
+			&mod_dir,
+			"Alpha Beta Patch",
+			&load_order,
+			&files,
+		)
+		.unwrap();
+		assert_eq!(patch.local_path, mod_dir.join("alpha_beta_patch"));
+		assert_eq!(patch.load_order, vec!["Alpha", "Beta", "Alpha Beta Patch"]);
+
+		let module = manager.find_module("Alpha Beta Patch").unwrap();
+		assert_eq!(module.meta.dependencies, load_order);
+		assert_eq!(Path::new(&module.meta.local_path), patch.local_path);
+		let relative_paths: Vec<&str> = module
+			.components
+			.iter()
+			.map(|component| component.relative_path())
+			.collect();
+		assert!(relative_paths.contains(&"common/ideas/00_basic_ideas.txt"));
+		assert!(relative_paths.contains(&"localisation/patch_l_english.yml"));
+		let localisation =
+			std::fs::read(patch.local_path.join("localisation/patch_l_english.yml")).unwrap();
+		assert!(localisation.starts_with("\u{feff}".as_bytes()));
+
+		// 不覆盖已存在的补丁
+		assert!(generate_patch(
+			&mut ModuleManager::new(),
+			&mod_dir,
+			"Alpha Beta Patch",
+			&[],
+			&[]
+		)
+		.is_err());
+		std::fs::remove_dir_all(&mod_dir).unwrap();
+	}
+}
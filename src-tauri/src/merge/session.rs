@@ -0,0 +1,307 @@
+use super::three_way::{MergeConflict, MergedFile};
+use md5::{Digest, Md5};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// 对一个冲突块的处理方式
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "text", rename_all = "snake_case")]
+pub enum Resolution {
+	Ours,
+	Theirs,
+	Both, // 先写出先加载一方的内容，再写出后加载一方的内容
+	Custom(String),
+}
+
+impl Resolution {
+	fn apply(&self, conflict: &MergeConflict) -> String {
+		match self {
+			Resolution::Ours => conflict.ours.clone().unwrap_or_default(),
+			Resolution::Theirs => conflict.theirs.clone().unwrap_or_default(),
+			Resolution::Both => format!(
+				"{}{}",
+				conflict.ours.as_deref().unwrap_or(""),
+				conflict.theirs.as_deref().unwrap_or("")
+			),
+			Resolution::Custom(text) => text.clone(),
+		}
+	}
+}
+
+/// 一次修改，撤销与重做时在 before 与 after 之间切换
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Change {
+	hunk: usize,
+	before: Option<Resolution>,
+	after: Option<Resolution>,
+}
+
+/// 手动合并某个文件的会话，保存到磁盘后可以继续编辑
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MergeSession {
+	pub id: String,
+	pub game_name: String,
+	pub mod_names: Vec<String>,
+	file: MergedFile,
+	resolutions: Vec<Option<Resolution>>, // 按冲突编号排列，None 表示尚未处理
+	undo_stack: Vec<Change>,
+	redo_stack: Vec<Change>,
+}
+
+/// 冲突块及其当前的处理方式
+#[derive(Debug, Clone, Serialize)]
+pub struct MergeHunk {
+	pub index: usize,
+	pub conflict: MergeConflict,
+	pub resolution: Option<Resolution>,
+}
+
+/// 返回给前端的会话状态
+#[derive(Debug, Clone, Serialize)]
+pub struct MergeSessionState {
+	pub id: String,
+	pub relative_path: String,
+	pub ours: String,
+	pub theirs: String,
+	pub hunks: Vec<MergeHunk>,
+	pub content: String, // 未处理的冲突带有注释标记
+	pub complete: bool,
+	pub can_undo: bool,
+	pub can_redo: bool,
+}
+
+/// 同一游戏、同一组 Mod 与同一文件的会话使用相同的编号，再次打开时可以继续上次的编辑
+pub fn session_id(game_name: &str, mod_names: &[String], relative_path: &str) -> String {
+	let mut hasher = Md5::new();
+	hasher.update(game_name.as_bytes());
+	for name in mod_names {
+		hasher.update([0]);
+		hasher.update(name.as_bytes());
+	}
+	hasher.update([0]);
+	hasher.update(relative_path.replace('\\', "/").to_lowercase().as_bytes());
+	hasher
+		.finalize()
+		.iter()
+		.map(|byte| format!("{:02x}", byte))
+		.collect()
+}
+
+impl MergeSession {
+	pub fn new(game_name: String, mod_names: Vec<String>, file: MergedFile) -> MergeSession {
+		MergeSession {
+			id: session_id(&game_name, &mod_names, &file.relative_path),
+			resolutions: vec![None; file.conflicts().len()],
+			game_name,
+			mod_names,
+			file,
+			undo_stack: Vec::new(),
+			redo_stack: Vec::new(),
+		}
+	}
+
+	/// 设置某个冲突块的处理方式，None 表示恢复为未处理，新的修改会清空重做记录
+	pub fn resolve(&mut self, hunk: usize, resolution: Option<Resolution>) -> Result<(), String> {
+		let current = self
+			.resolutions
+			.get_mut(hunk)
+			.ok_or_else(|| format!("Hunk {} not found", hunk))?;
+		if *current == resolution {
+			return Ok(());
+		}
+		let before = std::mem::replace(current, resolution.clone());
+		self.undo_stack.push(Change {
+			hunk,
+			before,
+			after: resolution,
+		});
+		self.redo_stack.clear();
+		Ok(())
+	}
+
+	pub fn undo(&mut self) -> bool {
+		match self.undo_stack.pop() {
+			Some(change) => {
+				self.resolutions[change.hunk] = change.before.clone();
+				self.redo_stack.push(change);
+				true
+			}
+			None => false,
+		}
+	}
+
+	pub fn redo(&mut self) -> bool {
+		match self.redo_stack.pop() {
+			Some(change) => {
+				self.resolutions[change.hunk] = change.after.clone();
+				self.undo_stack.push(change);
+				true
+			}
+			None => false,
+		}
+	}
+
+	pub fn is_complete(&self) -> bool {
+		self.resolutions.iter().all(Option::is_some)
+	}
+
+	/// 按当前的处理方式写出合并后的脚本
+	pub fn content(&self) -> String {
+		self.file.render(|index, conflict| {
+			self.resolutions[index]
+				.as_ref()
+				.map(|resolution| resolution.apply(conflict))
+		})
+	}
+
+	pub fn state(&self) -> MergeSessionState {
+		MergeSessionState {
+			id: self.id.clone(),
+			relative_path: self.file.relative_path.clone(),
+			ours: self.file.ours.clone(),
+			theirs: self.file.theirs.clone(),
+			hunks: self
+				.file
+				.conflicts()
+				.into_iter()
+				.zip(&self.resolutions)
+				.enumerate()
+				.map(|(index, (conflict, resolution))| MergeHunk {
+					index,
+					conflict: conflict.clone(),
+					resolution: resolution.clone(),
+				})
+				.collect(),
+			content: self.content(),
+			complete: self.is_complete(),
+			can_undo: !self.undo_stack.is_empty(),
+			can_redo: !self.redo_stack.is_empty(),
+		}
+	}
+
+	/// 会话编号只能是 session_id 生成的 32 位十六进制字符串，防止通过编号访问会话目录以外的文件
+	fn session_path(dir: &Path, id: &str) -> Result<PathBuf, String> {
+		if id.len() != 32 || !id.chars().all(|c| c.is_ascii_hexdigit()) {
+			return Err(format!("Invalid merge session id {}", id));
+		}
+		Ok(dir.join(format!("{}.json", id)))
+	}
+
+	pub fn save(&self, dir: &Path) -> Result<(), String> {
+		let path = MergeSession::session_path(dir, &self.id)?;
+		std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+		let content = serde_json::to_string(self).map_err(|e| e.to_string())?;
+		std::fs::write(path, content)
+			.map_err(|e| format!("Error saving merge session {}: {}", self.id, e))
+	}
+
+	pub fn load(dir: &Path, id: &str) -> Result<MergeSession, String> {
+		let path = MergeSession::session_path(dir, id)?;
+		let content = std::fs::read_to_string(&path)
+			.map_err(|e| format!("Error reading merge session {}: {}", id, e))?;
+		serde_json::from_str(&content)
+			.map_err(|e| format!("Error parsing merge session {}: {}", path.display(), e))
+	}
+
+	pub fn delete(dir: &Path, id: &str) -> Result<(), String> {
+		std::fs::remove_file(MergeSession::session_path(dir, id)?)
+			.map_err(|e| format!("Error deleting merge session {}: {}", id, e))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::merge::three_way::merge_script;
+
+	fn session() -> MergeSession {
+		let file = MergedFile {
+			relative_path: "common/test.txt".to_string(),
+			ours: "Alpha".to_string(),
+			theirs: "Beta".to_string(),
+			entries: merge_script(Some("a = 1\nb = 2"), "a = 3\nb = 4", "a = 5\nb = 6").unwrap(),
+		};
+		MergeSession::new(
+			"Europa Universalis IV".to_string(),
+			vec!["Alpha".to_string(), "Beta".to_string()],
+			file,
+		)
+	}
+
+	#[test]
+	fn test_resolve_and_undo() {
+		let mut session = session();
+		assert_eq!(session.state().hunks.len(), 2);
+		assert!(!session.is_complete());
+
+		session.resolve(0, Some(Resolution::Theirs)).unwrap();
+		session.resolve(1, Some(Resolution::Both)).unwrap();
+		assert!(session.is_complete());
+		assert_eq!(session.content(), "a = 5\nb = 4\nb = 6\n");
+
+		session
+			.resolve(1, Some(Resolution::Custom("b = 10\n".to_string())))
+			.unwrap();
+		assert_eq!(session.content(), "a = 5\nb = 10\n");
+		assert!(session.undo());
+		assert_eq!(session.content(), "a = 5\nb = 4\nb = 6\n");
+		assert!(session.undo());
+		assert!(session.undo());
+		assert!(!session.undo());
+		assert!(session.content().contains("# <<<<<<< Alpha"));
+
+		assert!(session.redo());
+		assert_eq!(
+			session.state().hunks[0].resolution,
+			Some(Resolution::Theirs)
+		);
+		// 新的修改清空重做记录
+		session.resolve(1, Some(Resolution::Ours)).unwrap();
+		assert!(!session.redo());
+		assert!(session.resolve(2, None).is_err());
+	}
+
+	#[test]
+	fn test_save_and_load() {
+		let dir = std::env::temp_dir().join(format!("pmm_sessions_{}", std::process::id()));
+		let mut session = session();
+		session.resolve(0, Some(Resolution::Ours)).unwrap();
+		session.save(&dir).unwrap();
+
+		let mut loaded = MergeSession::load(&dir, &session.id).unwrap();
+		assert_eq!(loaded.mod_names, session.mod_names);
+		assert_eq!(loaded.content(), session.content());
+		assert!(loaded.undo());
+		assert!(loaded.state().hunks[0].resolution.is_none());
+
+		MergeSession::delete(&dir, &session.id).unwrap();
+		assert!(MergeSession::load(&dir, &session.id).is_err());
+		std::fs::remove_dir_all(&dir).unwrap();
+	}
+
+	#[test]
+	fn test_session_id() {
+		let mods = vec!["Alpha".to_string()];
+		assert_eq!(
+			session_id("EU4", &mods, "Common\\Ideas\\a.txt"),
+			session_id("EU4", &mods, "common/ideas/a.txt")
+		);
+		assert_ne!(
+			session_id("EU4", &mods, "a.txt"),
+			session_id("EU4", &[], "a.txt")
+		);
+	}
+
+	#[test]
+	fn test_invalid_session_id() {
+		let dir = std::env::temp_dir().join(format!("pmm_invalid_{}", std::process::id()));
+		for id in ["../../x", "", "0123456789abcdef0123456789abcdeg"] {
+			assert!(MergeSession::load(&dir, id).is_err());
+			assert!(MergeSession::delete(&dir, id).is_err());
+		}
+		let mut session = session();
+		session.id = "../session".to_string();
+		assert!(session.save(&dir).is_err());
+		assert!(!dir.exists());
+	}
+}
@@ -0,0 +1,381 @@
+use crate::lang::script::{parse_script, write_script, ScriptEntry, ScriptValue};
+use crate::module::component::Component;
+use crate::module::module::Module;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// 双方都修改了同一对象且无法自动合并的冲突
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MergeConflict {
+	pub path: String, // 对象在脚本中的位置，如 innovativeness_ideas/bonus/technology_cost
+	pub base: Option<String>, // 原版中的写法，None 表示原版没有该对象
+	pub ours: Option<String>, // None 表示该 Mod 删除了该对象
+	pub theirs: Option<String>,
+}
+
+/// 合并结果中的一项
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum MergedEntry {
+	Resolved(ScriptEntry),
+	// 双方都修改了同一个代码块，逐项合并其中的子项
+	Merged {
+		key: String,
+		operator: String,
+		children: Vec<MergedEntry>,
+	},
+	Conflict(MergeConflict),
+}
+
+/// 以原版为基准合并两个 Mod 的同一脚本文件的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MergedFile {
+	pub relative_path: String,
+	pub ours: String,   // 先加载的 Mod
+	pub theirs: String, // 后加载的 Mod
+	pub entries: Vec<MergedEntry>,
+}
+
+/// 返回给前端的合并结果
+#[derive(Debug, Clone, Serialize)]
+pub struct MergeReport {
+	pub relative_path: String,
+	pub ours: String,
+	pub theirs: String,
+	pub content: String, // 冲突处带有注释标记
+	pub conflicts: Vec<MergeConflict>,
+}
+
+impl MergedFile {
+	/// 按出现顺序列出所有冲突，下标即冲突的编号
+	pub fn conflicts(&self) -> Vec<&MergeConflict> {
+		fn collect<'a>(entries: &'a [MergedEntry], conflicts: &mut Vec<&'a MergeConflict>) {
+			for entry in entries {
+				match entry {
+					MergedEntry::Conflict(conflict) => conflicts.push(conflict),
+					MergedEntry::Merged { children, .. } => collect(children, conflicts),
+					MergedEntry::Resolved(_) => {}
+				}
+			}
+		}
+		let mut conflicts = Vec::new();
+		collect(&self.entries, &mut conflicts);
+		conflicts
+	}
+
+	/// 写出合并后的脚本，`resolve` 按冲突编号给出解决后的文本，返回 None 的冲突写出双方内容与标记
+	pub fn render<F: Fn(usize, &MergeConflict) -> Option<String>>(&self, resolve: F) -> String {
+		let mut out = String::new();
+		let mut index = 0;
+		self.render_entries(&mut out, &self.entries, 0, &mut index, &resolve);
+		out
+	}
+
+	fn render_entries<F: Fn(usize, &MergeConflict) -> Option<String>>(
+		&self,
+		out: &mut String,
+		entries: &[MergedEntry],
+		depth: usize,
+		index: &mut usize,
+		resolve: &F,
+	) {
+		let indent = "\t".repeat(depth);
+		for entry in entries {
+			match entry {
+				MergedEntry::Resolved(entry) => {
+					out.push_str(&entry.to_script(depth));
+					out.push('\n');
+				}
+				MergedEntry::Merged {
+					key,
+					operator,
+					children,
+				} => {
+					out.push_str(&format!("{}{} {} {{\n", indent, key, operator));
+					self.render_entries(out, children, depth + 1, index, resolve);
+					out.push_str(&format!("{}}}\n", indent));
+				}
+				MergedEntry::Conflict(conflict) => {
+					match resolve(*index, conflict) {
+						Some(text) => push_indented(out, &text, &indent),
+						None => {
+							out.push_str(&format!("{}# <<<<<<< {}\n", indent, self.ours));
+							push_indented(out, conflict.ours.as_deref().unwrap_or(""), &indent);
+							out.push_str(&format!("{}# =======\n", indent));
+							push_indented(out, conflict.theirs.as_deref().unwrap_or(""), &indent);
+							out.push_str(&format!("{}# >>>>>>> {}\n", indent, self.theirs));
+						}
+					}
+					*index += 1;
+				}
+			}
+		}
+	}
+
+	pub fn to_script(&self) -> String {
+		self.render(|_, _| None)
+	}
+
+	pub fn report(&self) -> MergeReport {
+		MergeReport {
+			relative_path: self.relative_path.clone(),
+			ours: self.ours.clone(),
+			theirs: self.theirs.clone(),
+			content: self.to_script(),
+			conflicts: self.conflicts().into_iter().cloned().collect(),
+		}
+	}
+}
+
+/// 冲突文本按所在层级缩进
+fn push_indented(out: &mut String, text: &str, indent: &str) {
+	for line in text.lines() {
+		out.push_str(indent);
+		out.push_str(line);
+		out.push('\n');
+	}
+}
+
+fn entry_text(entry: &ScriptEntry) -> String {
+	write_script(std::slice::from_ref(entry))
+}
+
+/// 对象的标识：键名加上同名对象的序号，列表中的值以其文本作为标识
+fn object_ids(entries: &[ScriptEntry]) -> Vec<(String, &ScriptEntry)> {
+	let mut seen: HashMap<String, usize> = HashMap::new();
+	entries
+		.iter()
+		.map(|entry| {
+			let name = match &entry.key {
+				Some(key) => key.clone(),
+				None => entry_text(entry).trim_end().to_string(),
+			};
+			let count = seen.entry(name.clone()).or_default();
+			let id = format!("{}#{}", name, count);
+			*count += 1;
+			(id, entry)
+		})
+		.collect()
+}
+
+/// 保留先加载一方的顺序，后加载一方新增的对象插入到它在原文件中的前一个对象之后
+fn merged_order(ours: &[(String, &ScriptEntry)], theirs: &[(String, &ScriptEntry)]) -> Vec<String> {
+	let mut order: Vec<String> = ours.iter().map(|(id, _)| id.clone()).collect();
+	let mut previous: Option<&String> = None;
+	for (id, _) in theirs {
+		if !order.contains(id) {
+			let position = previous
+				.and_then(|previous| order.iter().position(|other| other == previous))
+				.map_or(0, |position| position + 1);
+			order.insert(position, id.clone());
+		}
+		previous = Some(id);
+	}
+	order
+}
+
+fn find<'a>(entries: &[(String, &'a ScriptEntry)], id: &str) -> Option<&'a ScriptEntry> {
+	entries
+		.iter()
+		.find(|(other, _)| other == id)
+		.map(|(_, entry)| *entry)
+}
+
+fn block_children(entry: &ScriptEntry) -> Option<&[ScriptEntry]> {
+	match &entry.value {
+		ScriptValue::Block(children) => Some(children),
+		_ => None,
+	}
+}
+
+fn merge_entries(
+	base: &[ScriptEntry],
+	ours: &[ScriptEntry],
+	theirs: &[ScriptEntry],
+	prefix: &str,
+) -> Vec<MergedEntry> {
+	let base = object_ids(base);
+	let ours = object_ids(ours);
+	let theirs = object_ids(theirs);
+
+	let mut merged = Vec::new();
+	for id in merged_order(&ours, &theirs) {
+		let (b, a, c) = (find(&base, &id), find(&ours, &id), find(&theirs, &id));
+		let (tb, ta, tc) = (b.map(entry_text), a.map(entry_text), c.map(entry_text));
+		let chosen = if ta == tc || tb == tc {
+			a.cloned().map(MergedEntry::Resolved)
+		} else if tb == ta {
+			c.cloned().map(MergedEntry::Resolved)
+		} else {
+			let path = match id.rsplit_once('#') {
+				Some((name, "0")) => format!("{}{}", prefix, name),
+				_ => format!("{}{}", prefix, id),
+			};
+			// 双方都修改了同一个代码块时逐项合并子项，原版没有该对象时视为空代码块
+			let blocks = (
+				b.map_or(Some(&[][..]), block_children),
+				a.and_then(block_children),
+				c.and_then(block_children),
+			);
+			match (a, c, blocks) {
+				(Some(a), Some(c), (Some(bc), Some(ac), Some(cc)))
+					if a.key.is_some() && a.key == c.key && a.operator == c.operator =>
+				{
+					Some(MergedEntry::Merged {
+						key: a.key.clone().unwrap(),
+						operator: a.operator.clone().unwrap_or_else(|| "=".to_string()),
+						children: merge_entries(bc, ac, cc, &format!("{}/", path)),
+					})
+				}
+				_ => Some(MergedEntry::Conflict(MergeConflict {
+					path,
+					base: tb,
+					ours: ta,
+					theirs: tc,
+				})),
+			}
+		};
+		merged.extend(chosen);
+	}
+	merged
+}
+
+/// 以原版脚本为基准，按对象合并两个 Mod 的修改
+pub fn merge_script(
+	base: Option<&str>,
+	ours: &str,
+	theirs: &str,
+) -> Result<Vec<MergedEntry>, String> {
+	let base = match base {
+		Some(base) => parse_script(base)?,
+		None => Vec::new(),
+	};
+	Ok(merge_entries(
+		&base,
+		&parse_script(ours)?,
+		&parse_script(theirs)?,
+		"",
+	))
+}
+
+/// 合并两个 Mod 提供的同一脚本文件，原版中的同名文件作为基准
+pub fn merge_file(
+	vanilla: &[Component],
+	modules: &[&Module],
+	relative_path: &str,
+) -> Result<MergedFile, String> {
+	let relative_path = relative_path.replace('\\', "/").to_lowercase();
+	if !relative_path.ends_with(".txt") {
+		return Err(format!("{} is not a script file", relative_path));
+	}
+	let find = |components: &[Component]| {
+		components
+			.iter()
+			.find(|component| component.relative_path() == relative_path)
+			.map(|component| component.read_text().map_err(|e| e.to_string()))
+			.transpose()
+	};
+
+	let mut providers = Vec::new();
+	for module in modules {
+		if let Some(content) = find(&module.components)? {
+			providers.push((module.meta.name.clone(), content));
+		}
+	}
+	let [(ours, ours_content), (theirs, theirs_content)]: [(String, String); 2] =
+		providers.try_into().map_err(|providers: Vec<_>| {
+			format!(
+				"Merging {} requires exactly two mods providing it, found {}",
+				relative_path,
+				providers.len()
+			)
+		})?;
+	let base = find(vanilla)?;
+
+	Ok(MergedFile {
+		entries: merge_script(base.as_deref(), &ours_content, &theirs_content)?,
+		relative_path,
+		ours,
+		theirs,
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::manager::ModuleManager;
+	use std::path::Path;
+
+	#[test]
+	fn test_merge_script() {
+		let base = "a = 1\nb = 2\nc = 3";
+		let ours = "a = 1\nb = 5\nc = 3\nd = 4";
+		let theirs = "a = 9\nb = 2\ne = 6";
+		let file = MergedFile {
+			relative_path: "common/test.txt".to_string(),
+			ours: "Alpha".to_string(),
+			theirs: "Beta".to_string(),
+			entries: merge_script(Some(base), ours, theirs).unwrap(),
+		};
+		assert!(file.conflicts().is_empty());
+		assert_eq!(file.to_script(), "a = 9\nb = 5\ne = 6\nd = 4\n");
+	}
+
+	#[test]
+	fn test_modify_delete_conflict() {
+		let entries = merge_script(Some("a = { x = 1 }"), "a = { x = 2 }", "").unwrap();
+		assert_eq!(
+			entries,
+			vec![MergedEntry::Conflict(MergeConflict {
+				path: "a".to_string(),
+				base: Some("a = {\n\tx = 1\n}\n".to_string()),
+				ours: Some("a = {\n\tx = 2\n}\n".to_string()),
+				theirs: None,
+			})]
+		);
+	}
+
+	#[test]
+	fn test_merge_file() {
+		let resources = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/resources");
+		let vanilla = Component::get_components_from_dir(resources.join("vanilla")).unwrap();
+		let mut manager = ModuleManager::new();
+		manager.load_mod_dir(resources.join("mods"));
+		let modules = manager
+			.modules_in_order(&["Alpha".to_string(), "Beta".to_string()])
+			.unwrap();
+		let modules: Vec<&Module> = modules.iter().map(|m| m.as_ref()).collect();
+		let file = merge_file(&vanilla, &modules, "Common/Ideas/00_basic_ideas.txt").unwrap();
+		assert_eq!(file.ours, "Alpha");
+		assert_eq!(file.theirs, "Beta");
+
+		// 只有 technology_cost 被双方改成了不同的值，patron_of_art 只有 Beta 修改
+		let conflicts = file.conflicts();
+		assert_eq!(conflicts.len(), 1);
+		assert_eq!(
+			conflicts[0].path,
+			"innovativeness_ideas/bonus/technology_cost"
+		);
+		assert_eq!(
+			conflicts[0].base.as_deref(),
+			Some("technology_cost = -0.2\n")
+		);
+		assert_eq!(
+			conflicts[0].ours.as_deref(),
+			Some("technology_cost = -0.1\n")
+		);
+		assert_eq!(
+			conflicts[0].theirs.as_deref(),
+			Some("technology_cost = -0.05\n")
+		);
+
+		assert_eq!(
+			file.to_script(),
+			"innovativeness_ideas = {\n\tcategory = ADM\n\tbonus = {\n\t\t# <<<<<<< Alpha\n\t\ttechnology_cost = -0.1\n\t\t# =======\n\t\ttechnology_cost = -0.05\n\t\t# >>>>>>> Beta\n\t}\n\tpatron_of_art = {\n\t\tprestige = 2\n\t}\n}\n"
+		);
+		let resolved = file.render(|_, conflict| conflict.theirs.clone());
+		assert!(resolved.contains("\t\ttechnology_cost = -0.05\n"));
+		assert!(!resolved.contains('#'));
+
+		assert!(merge_file(&vanilla, &modules, "common/ideas/alpha_ideas.txt").is_err());
+	}
+}
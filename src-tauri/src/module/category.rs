@@ -0,0 +1,118 @@
+use serde::Serialize;
+
+/// 组件所属的游戏目录，由虚拟路径的前缀决定
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+#[serde(tag = "kind", content = "folder", rename_all = "snake_case")]
+pub enum Category {
+	Common(String), // common/<子目录>，如 common/ideas
+	Events,
+	Decisions,
+	Missions,
+	History(String), // history/<子目录>，如 history/countries
+	Gfx,
+	Interface,
+	Localisation,
+	Map,
+	Music,
+	Sound,
+	Root,          // Mod 根目录下的文件，如 descriptor.mod、thumbnail.png
+	Other(String), // 其他顶层目录
+}
+
+impl Category {
+	/// 根据已规范化的虚拟路径（如 `common/ideas/00_basic_ideas.txt`）进行分类
+	pub fn from_relative_path(relative_path: &str) -> Category {
+		let mut segments = relative_path.split('/');
+		let top = segments.next().unwrap_or_default();
+		// 只有顶层文件时，没有所属目录
+		let sub = match segments.next() {
+			Some(sub) => sub,
+			None => return Category::Root,
+		};
+		// 对于 common/ 与 history/，子目录本身就是一个独立的游戏目录
+		let sub_folder = if segments.next().is_some() {
+			sub.to_string()
+		} else {
+			String::new()
+		};
+
+		match top {
+			"common" => Category::Common(sub_folder),
+			"history" => Category::History(sub_folder),
+			"events" => Category::Events,
+			"decisions" => Category::Decisions,
+			"missions" => Category::Missions,
+			"gfx" => Category::Gfx,
+			"interface" => Category::Interface,
+			"localisation" | "localization" => Category::Localisation,
+			"map" => Category::Map,
+			"music" => Category::Music,
+			"sound" => Category::Sound,
+			other => Category::Other(other.to_string()),
+		}
+	}
+
+	/// 该分类对应的游戏目录，如 `common/ideas`、`events`
+	pub fn folder(&self) -> String {
+		match self {
+			Category::Common(sub) if !sub.is_empty() => format!("common/{}", sub),
+			Category::Common(_) => "common".to_string(),
+			Category::History(sub) if !sub.is_empty() => format!("history/{}", sub),
+			Category::History(_) => "history".to_string(),
+			Category::Events => "events".to_string(),
+			Category::Decisions => "decisions".to_string(),
+			Category::Missions => "missions".to_string(),
+			Category::Gfx => "gfx".to_string(),
+			Category::Interface => "interface".to_string(),
+			Category::Localisation => "localisation".to_string(),
+			Category::Map => "map".to_string(),
+			Category::Music => "music".to_string(),
+			Category::Sound => "sound".to_string(),
+			Category::Root => String::new(),
+			Category::Other(top) => top.clone(),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_category_from_relative_path() {
+		assert_eq!(
+			Category::from_relative_path("common/ideas/00_basic_ideas.txt"),
+			Category::Common("ideas".to_string())
+		);
+		assert_eq!(
+			Category::from_relative_path("history/countries/SWE - Sweden.txt"),
+			Category::History("countries".to_string())
+		);
+		assert_eq!(
+			Category::from_relative_path("events/flavorswe.txt"),
+			Category::Events
+		);
+		assert_eq!(
+			Category::from_relative_path("localization/english/foo_l_english.yml"),
+			Category::Localisation
+		);
+		assert_eq!(
+			Category::from_relative_path("descriptor.mod"),
+			Category::Root
+		);
+		assert_eq!(
+			Category::from_relative_path("tweakergui_assets/foo.dds"),
+			Category::Other("tweakergui_assets".to_string())
+		);
+	}
+
+	#[test]
+	fn test_category_folder() {
+		assert_eq!(
+			Category::Common("ideas".to_string()).folder(),
+			"common/ideas"
+		);
+		assert_eq!(Category::Common(String::new()).folder(), "common");
+		assert_eq!(Category::Localisation.folder(), "localisation");
+	}
+}
@@ -1,49 +1,75 @@
-use crate::utility::filesystem::visit_dir;
+use super::category::Category;
+use crate::utility::filesystem::{to_virtual_path, visit_dir};
 use derive_builder::Builder;
 use md5::{Digest, Md5};
 use std::error::Error;
 use std::fs::File;
 use std::io::{BufReader, Read};
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use zip::ZipArchive;
 
 #[derive(Builder, Debug, Clone)]
 pub struct Component {
 	name: String,
 	path: PathBuf,
+	relative_path: String, // 相对于 Mod 根目录的规范化虚拟路径
+	category: Category,
 	hash: [u8; 16],
+	#[builder(default)]
+	contents: Option<Arc<[u8]>>, // 压缩包中的条目在扫描时已解压，保留内容以免读取时重新打开压缩包
 }
 
 impl Component {
-	pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Component, Box<dyn Error>> {
+	pub fn from_path<P: AsRef<Path>, Q: AsRef<Path>>(
+		root: P,
+		path: Q,
+	) -> Result<Component, Box<dyn Error>> {
 		let path_ref = path.as_ref();
 
+		// 计算虚拟路径与所属目录
+		let relative_path =
+			to_virtual_path(&root, path_ref).ok_or("File is not inside the mod root")?;
+
+		// 打开文件
+		let file = File::open(path_ref)?;
+		let mut reader = BufReader::new(file);
+		let mut buffer = Vec::new();
+		reader.read_to_end(&mut buffer)?;
+
+		Component::from_bytes(path_ref, relative_path, &buffer, None)
+	}
+
+	/// 由文件内容构建组件，目录中的文件与压缩包中的条目共用
+	fn from_bytes(
+		path: &Path,
+		relative_path: String,
+		bytes: &[u8],
+		contents: Option<Arc<[u8]>>,
+	) -> Result<Component, Box<dyn Error>> {
+		let category = Category::from_relative_path(&relative_path);
+
 		// 处理文件名提取
-		let file_name = path_ref
+		let file_name = path
 			.file_name()
 			.ok_or("Invalid file name")?
 			.to_str()
 			.ok_or("Invalid UTF-8 in file name")?
 			.to_string();
 
-		// 打开文件
-		let file = File::open(path_ref)?;
-		let mut reader = BufReader::new(file);
-
+		// 计算哈希值
 		let mut hasher = Md5::new();
-		let mut buffer = Vec::new();
-
-		// 逐字节读取并更新哈希
-		reader.read_to_end(&mut buffer)?;
-		hasher.update(&buffer);
-
-		// 计算最终哈希值
+		hasher.update(bytes);
 		let hash = hasher.finalize();
 
 		// 构建组件
 		Ok(ComponentBuilder::default()
 			.name(file_name)
-			.path(path_ref.to_path_buf())
+			.path(path.to_path_buf())
+			.relative_path(relative_path)
+			.category(category)
 			.hash(hash.into())
+			.contents(contents)
 			.build()?)
 	}
 
@@ -54,7 +80,7 @@ impl Component {
 		let files = visit_dir(&dir)?;
 
 		for file in files {
-			let component = Component::from_path(file);
+			let component = Component::from_path(&dir, file);
 			match component {
 				Ok(component) => components.push(component),
 				Err(e) => eprintln!(
@@ -67,6 +93,88 @@ impl Component {
 
 		Ok(components)
 	}
+
+	/// 读取 zip 压缩包中的全部文件，压缩包的根目录即 Mod 根目录
+	pub fn get_components_from_archive<P: AsRef<Path>>(
+		archive_path: P,
+	) -> Result<Vec<Component>, Box<dyn Error>> {
+		let archive_path = archive_path.as_ref();
+		let mut archive = ZipArchive::new(File::open(archive_path)?)?;
+		let mut components = Vec::new();
+
+		for index in 0..archive.len() {
+			let mut entry = archive.by_index(index)?;
+			if entry.is_dir() {
+				continue;
+			}
+			// 忽略带有 .. 或绝对路径的条目
+			let Some(enclosed) = entry.enclosed_name() else {
+				eprintln!(
+					"Skipping unsafe entry {} in archive {}",
+					entry.name(),
+					archive_path.display()
+				);
+				continue;
+			};
+			let path = archive_path.join(enclosed);
+			let Some(relative_path) = to_virtual_path(archive_path, &path) else {
+				continue;
+			};
+			let mut buffer = Vec::new();
+			entry.read_to_end(&mut buffer)?;
+			let buffer: Arc<[u8]> = buffer.into();
+			match Component::from_bytes(&path, relative_path, &buffer, Some(buffer.clone())) {
+				Ok(component) => components.push(component),
+				Err(e) => eprintln!(
+					"Error getting component from entry {} in archive {}: {}",
+					entry.name(),
+					archive_path.display(),
+					e
+				),
+			}
+		}
+
+		Ok(components)
+	}
+
+	pub fn name(&self) -> &str {
+		&self.name
+	}
+
+	pub fn path(&self) -> &Path {
+		&self.path
+	}
+
+	pub fn relative_path(&self) -> &str {
+		&self.relative_path
+	}
+
+	pub fn category(&self) -> &Category {
+		&self.category
+	}
+
+	pub fn hash(&self) -> &[u8; 16] {
+		&self.hash
+	}
+
+	/// 读取文件的文本内容，去除 UTF-8 BOM，无法识别的字节按替换字符处理
+	pub fn read_text(&self) -> Result<String, Box<dyn Error>> {
+		let read;
+		let bytes = match &self.contents {
+			Some(contents) => contents.as_ref(),
+			None => {
+				read = std::fs::read(&self.path)?;
+				read.as_slice()
+			}
+		};
+		let text = String::from_utf8_lossy(bytes);
+		Ok(text.trim_start_matches('\u{feff}').to_string())
+	}
+
+	/// 以十六进制字符串表示的哈希值，便于序列化与比较
+	pub fn hash_hex(&self) -> String {
+		self.hash.iter().map(|byte| format!("{:02x}", byte)).collect()
+	}
 }
 
 #[cfg(test)]
@@ -83,4 +191,58 @@ mod tests {
 		// let components = Component::get_components_from_dir(test_dir).unwrap();
 		// println!("{:?}", components);
 	}
+
+	#[test]
+	fn test_component_relative_path() {
+		let mod_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/resources/mods/beta");
+		let components = Component::get_components_from_dir(&mod_dir).unwrap();
+		let ideas = components
+			.iter()
+			.find(|c| c.name() == "00_basic_ideas.txt")
+			.unwrap();
+		assert_eq!(ideas.relative_path(), "common/ideas/00_basic_ideas.txt");
+		assert_eq!(ideas.category(), &Category::Common("ideas".to_string()));
+		assert_eq!(ideas.category().folder(), "common/ideas");
+
+		let descriptor = components
+			.iter()
+			.find(|c| c.name() == "descriptor.mod")
+			.unwrap();
+		assert_eq!(descriptor.relative_path(), "descriptor.mod");
+		assert_eq!(descriptor.category(), &Category::Root);
+	}
+
+	#[test]
+	fn test_component_archive() {
+		let resources = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/resources");
+		let mut unpacked =
+			Component::get_components_from_dir(resources.join("mods/alpha")).unwrap();
+		let mut archived =
+			Component::get_components_from_archive(resources.join("archive/zeta.zip")).unwrap();
+		unpacked.sort_by(|a, b| a.relative_path().cmp(b.relative_path()));
+		archived.sort_by(|a, b| a.relative_path().cmp(b.relative_path()));
+
+		// 压缩包中的目录条目不是组件，其余与解压后的目录完全一致
+		assert_eq!(archived.len(), unpacked.len());
+		for (archived, unpacked) in archived.iter().zip(&unpacked) {
+			assert_eq!(archived.relative_path(), unpacked.relative_path());
+			assert_eq!(archived.name(), unpacked.name());
+			assert_eq!(archived.category(), unpacked.category());
+			assert_eq!(archived.hash(), unpacked.hash());
+			assert_eq!(archived.read_text().unwrap(), unpacked.read_text().unwrap());
+		}
+		let history = archived
+			.iter()
+			.find(|c| c.relative_path() == "history/countries/alp - alpha.txt")
+			.unwrap();
+		assert!(history.path().starts_with(resources.join("archive/zeta.zip")));
+		assert!(Component::get_components_from_archive(resources.join("dlc_load.json")).is_err());
+
+		// 读取内容时不再打开压缩包
+		let copy = std::env::temp_dir().join(format!("pmm_zeta_{}.zip", std::process::id()));
+		std::fs::copy(resources.join("archive/zeta.zip"), &copy).unwrap();
+		let copied = Component::get_components_from_archive(&copy).unwrap();
+		std::fs::remove_file(&copy).unwrap();
+		assert!(copied.iter().all(|c| c.read_text().is_ok()));
+	}
 }
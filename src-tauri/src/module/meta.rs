@@ -1,6 +1,6 @@
-use crate::lang::parser::parse_content;
+use crate::lang::parser::{parse_content, parse_pairs};
 use derive_builder::Builder;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 #[derive(Builder, Debug, Clone)]
 pub struct Meta {
@@ -8,6 +8,14 @@ pub struct Meta {
 	pub version: Option<String>,
 	pub local_path: String,
 	pub dependencies: Vec<String>,
+	#[builder(default)]
+	pub replace_paths: Vec<String>, // 规范化后的 replace_path，按出现顺序排列
+	#[builder(default)]
+	pub mod_file: Option<PathBuf>, // 读取该 Mod 时使用的 .mod 文件
+	#[builder(default)]
+	pub remote_file_id: Option<String>, // 创意工坊物品 id
+	#[builder(default)]
+	pub archive: Option<String>, // 以 zip 压缩包形式安装时为压缩包路径，同时也是 local_path
 }
 
 impl Meta {
@@ -22,6 +30,10 @@ impl Meta {
 			version: Some(version),
 			local_path,
 			dependencies,
+			replace_paths: Vec::new(),
+			mod_file: None,
+			remote_file_id: None,
+			archive: None,
 		}
 	}
 
@@ -30,6 +42,20 @@ impl Meta {
 	) -> Result<Meta, Box<dyn std::error::Error>> {
 		let content = std::fs::read_to_string(&file_path)?;
 		let parsed = parse_content(&content)?;
+		// replace_path 可以出现多次，需要保留全部的值
+		let replace_paths = parse_pairs(&content)?
+			.iter()
+			.filter(|(key, _)| key == "replace_path")
+			.filter_map(|(_, value)| value.as_str())
+			.map(normalize_replace_path)
+			.collect();
+		// path 与 archive 同时存在时以 path 为准
+		let archive = match (parsed.get("path"), parsed.get("archive")) {
+			(None, Some(archive)) => archive
+				.as_string()
+				.map(|archive| resolve_local_path(&file_path, &archive)),
+			_ => None,
+		};
 
 		Ok(MetaBuilder::default()
 			.name(parsed.get("name").unwrap().as_string().unwrap())
@@ -37,9 +63,14 @@ impl Meta {
 				Some(version) => Some(version.as_string().unwrap()),
 				None => None,
 			})
-			.local_path(match parsed.get("path") {
-				Some(path) => path.as_string().unwrap(),
-				None => file_path.as_ref().to_str().unwrap().to_string(),
+			.local_path(match (parsed.get("path"), &archive) {
+				(Some(path), _) => resolve_local_path(&file_path, &path.as_string().unwrap()),
+				(None, Some(archive)) => archive.clone(),
+				// 没有 path 字段时，说明这是位于 Mod 根目录下的 descriptor.mod
+				(None, None) => match file_path.as_ref().parent() {
+					Some(dir) => dir.to_str().unwrap().to_string(),
+					None => file_path.as_ref().to_str().unwrap().to_string(),
+				},
 			})
 			.dependencies(match parsed.get("dependencies") {
 				Some(deps) => deps
@@ -50,7 +81,146 @@ impl Meta {
 					.collect(),
 				None => Vec::new(),
 			})
+			.replace_paths(replace_paths)
+			.mod_file(Some(file_path.as_ref().to_path_buf()))
+			.remote_file_id(
+				parsed
+					.get("remote_file_id")
+					.and_then(|id| id.as_string()),
+			)
+			.archive(archive)
 			.build()
 			.unwrap())
 	}
+
+	/// 写出 .mod 文件的内容，外层 .mod 文件还需要写出指向 Mod 文件夹的 path 字段
+	pub fn to_mod_file(&self, with_path: bool) -> String {
+		let mut content = format!("name=\"{}\"\n", self.name);
+		if let Some(version) = &self.version {
+			content.push_str(&format!("version=\"{}\"\n", version));
+		}
+		if let Some(remote_file_id) = &self.remote_file_id {
+			content.push_str(&format!("remote_file_id=\"{}\"\n", remote_file_id));
+		}
+		for replace_path in &self.replace_paths {
+			content.push_str(&format!("replace_path=\"{}\"\n", replace_path));
+		}
+		if !self.dependencies.is_empty() {
+			content.push_str("dependencies={\n");
+			for dependency in &self.dependencies {
+				content.push_str(&format!("\t\"{}\"\n", dependency));
+			}
+			content.push_str("}\n");
+		}
+		if with_path {
+			let key = match self.archive {
+				Some(_) => "archive",
+				None => "path",
+			};
+			content.push_str(&format!(
+				"{}=\"{}\"\n",
+				key,
+				self.local_path.replace('\\', "/")
+			));
+		}
+		content
+	}
+}
+
+/// 与组件的虚拟路径采用相同的规范化方式
+fn normalize_replace_path(path: &str) -> String {
+	path.trim()
+		.replace('\\', "/")
+		.trim_matches('/')
+		.to_lowercase()
+}
+
+/// 外层 .mod 文件中的相对路径（如 `mod/defines`）是相对于游戏用户目录的，
+/// 即 .mod 文件所在 mod 目录的上一级
+fn resolve_local_path<P: AsRef<Path>>(file_path: P, path: &str) -> String {
+	let local_path = PathBuf::from(path);
+	// 形如 `C:/...` 的 Windows 路径在其他平台上也视为绝对路径
+	if local_path.is_absolute() || path.chars().nth(1) == Some(':') {
+		return path.to_string();
+	}
+	match file_path.as_ref().parent().and_then(|dir| dir.parent()) {
+		Some(user_dir) => user_dir.join(local_path).to_str().unwrap().to_string(),
+		None => path.to_string(),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_resolve_local_path() {
+		let mod_file =
+			Path::new("/home/user/Paradox Interactive/Europa Universalis IV/mod/defines.mod");
+		assert_eq!(
+			resolve_local_path(mod_file, "mod/defines"),
+			"/home/user/Paradox Interactive/Europa Universalis IV/mod/defines"
+		);
+		assert_eq!(
+			resolve_local_path(mod_file, "C:/Users/actur/mod/defines"),
+			"C:/Users/actur/mod/defines"
+		);
+	}
+
+	#[test]
+	fn test_create_from_mod_file() {
+		let mod_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/resources/mods");
+
+		let outer = Meta::create_from_mod_file(mod_dir.join("alpha.mod")).unwrap();
+		assert_eq!(outer.name, "Alpha");
+		assert_eq!(outer.version, Some("1.0.0".to_string()));
+		assert_eq!(Path::new(&outer.local_path), mod_dir.join("alpha"));
+		assert_eq!(outer.mod_file, Some(mod_dir.join("alpha.mod")));
+
+		let descriptor = Meta::create_from_mod_file(mod_dir.join("alpha/descriptor.mod")).unwrap();
+		assert_eq!(Path::new(&descriptor.local_path), mod_dir.join("alpha"));
+		assert!(descriptor.replace_paths.is_empty());
+
+		let beta = Meta::create_from_mod_file(mod_dir.join("beta.mod")).unwrap();
+		assert_eq!(beta.replace_paths, vec!["history/countries"]);
+	}
+
+	#[test]
+	fn test_to_mod_file() {
+		let mod_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/resources/mods");
+		let mut beta = Meta::create_from_mod_file(mod_dir.join("beta.mod")).unwrap();
+		beta.dependencies = vec!["Alpha".to_string()];
+		let content = beta.to_mod_file(true);
+		assert!(content.contains("replace_path=\"history/countries\"\n"));
+		assert!(content.contains("dependencies={\n\t\"Alpha\"\n}\n"));
+
+		let file = std::env::temp_dir().join(format!("pmm_meta_{}.mod", std::process::id()));
+		std::fs::write(&file, &content).unwrap();
+		let parsed = Meta::create_from_mod_file(&file).unwrap();
+		std::fs::remove_file(&file).unwrap();
+		assert_eq!(parsed.name, "Beta");
+		assert_eq!(parsed.dependencies, vec!["Alpha"]);
+		assert_eq!(parsed.replace_paths, beta.replace_paths);
+		assert_eq!(parsed.local_path, beta.local_path.replace('\\', "/"));
+		assert!(!beta.to_mod_file(false).contains("\npath="));
+	}
+
+	#[test]
+	fn test_archive() {
+		let resources = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/resources");
+		let zeta = Meta::create_from_mod_file(resources.join("archive/zeta.mod")).unwrap();
+		assert_eq!(Path::new(&zeta.local_path), resources.join("archive/zeta.zip"));
+		assert_eq!(zeta.archive.as_deref(), Some(zeta.local_path.as_str()));
+		let content = zeta.to_mod_file(true);
+		assert!(content.contains("\narchive=\""));
+		assert!(!content.contains("\npath="));
+	}
+
+	#[test]
+	fn test_normalize_replace_path() {
+		assert_eq!(
+			normalize_replace_path("History\\Countries/"),
+			"history/countries"
+		);
+	}
 }
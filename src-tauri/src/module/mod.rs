@@ -1,6 +1,7 @@
 pub mod dependency;
 pub mod meta;
 
+pub mod category;
 pub mod component;
 
 pub mod module;
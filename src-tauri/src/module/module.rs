@@ -2,6 +2,7 @@ use super::component::Component;
 use super::dependency::Dependency;
 use super::meta::Meta;
 use derive_builder::Builder;
+use md5::{Digest, Md5};
 
 #[derive(Builder, Debug)]
 pub struct Module {
@@ -20,4 +21,21 @@ impl Module {
 			is_valid,
 		}
 	}
+
+	/// 由全部组件的虚拟路径与哈希计算出的整体哈希，与文件的读取顺序无关
+	pub fn content_hash(&self) -> String {
+		let mut components: Vec<&Component> = self.components.iter().collect();
+		components.sort_by(|a, b| a.relative_path().cmp(b.relative_path()));
+		let mut hasher = Md5::new();
+		for component in components {
+			hasher.update(component.relative_path().as_bytes());
+			hasher.update([0]);
+			hasher.update(component.hash());
+		}
+		hasher
+			.finalize()
+			.iter()
+			.map(|byte| format!("{:02x}", byte))
+			.collect()
+	}
 }
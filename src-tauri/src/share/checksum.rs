@@ -0,0 +1,213 @@
+use crate::module::module::Module;
+use md5::{Digest, Md5};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// 单个 Mod 的指纹，记录每个文件的哈希以便定位差异
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModFingerprint {
+	pub name: String,
+	pub hash: String,                    // Module::content_hash
+	pub files: BTreeMap<String, String>, // 虚拟路径 -> 文件哈希
+}
+
+/// 整个播放集的指纹，校验值相同即说明各玩家的 Mod 内容与加载顺序一致
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlaysetFingerprint {
+	pub game_name: String,
+	pub checksum: String,
+	pub mods: Vec<ModFingerprint>, // 按加载顺序排列
+}
+
+/// 两边都有但内容不同的 Mod
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ModDifference {
+	pub name: String,
+	pub changed_files: Vec<String>,
+	pub only_in_ours: Vec<String>,
+	pub only_in_theirs: Vec<String>,
+}
+
+/// 两份指纹的比较结果
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FingerprintComparison {
+	pub identical: bool,
+	pub order_differs: bool, // 共有的 Mod 加载顺序不同
+	pub only_in_ours: Vec<String>,
+	pub only_in_theirs: Vec<String>,
+	pub differences: Vec<ModDifference>,
+}
+
+impl ModFingerprint {
+	pub fn from_module(module: &Module) -> ModFingerprint {
+		ModFingerprint {
+			name: module.meta.name.clone(),
+			hash: module.content_hash(),
+			files: module
+				.components
+				.iter()
+				.map(|component| (component.relative_path().to_string(), component.hash_hex()))
+				.collect(),
+		}
+	}
+}
+
+impl PlaysetFingerprint {
+	pub fn from_modules(game_name: &str, modules: &[&Module]) -> PlaysetFingerprint {
+		let mods: Vec<ModFingerprint> = modules
+			.iter()
+			.map(|module| ModFingerprint::from_module(module))
+			.collect();
+		// 校验值依赖加载顺序，调换两个 Mod 也会得到不同的结果
+		let mut hasher = Md5::new();
+		for fingerprint in &mods {
+			hasher.update(fingerprint.name.as_bytes());
+			hasher.update([0]);
+			hasher.update(fingerprint.hash.as_bytes());
+		}
+		PlaysetFingerprint {
+			game_name: game_name.to_string(),
+			checksum: hasher
+				.finalize()
+				.iter()
+				.map(|byte| format!("{:02x}", byte))
+				.collect(),
+			mods,
+		}
+	}
+
+	pub fn parse(text: &str) -> Result<PlaysetFingerprint, String> {
+		serde_json::from_str(text).map_err(|e| format!("Error parsing fingerprint: {}", e))
+	}
+
+	/// 按名称对应两边的 Mod，找出缺少的 Mod 以及内容不同的文件
+	pub fn compare(&self, theirs: &PlaysetFingerprint) -> FingerprintComparison {
+		let find = |fingerprint: &PlaysetFingerprint, name: &str| {
+			fingerprint.mods.iter().position(|m| m.name == name)
+		};
+		let only_in_ours = self
+			.mods
+			.iter()
+			.filter(|m| find(theirs, &m.name).is_none())
+			.map(|m| m.name.clone())
+			.collect();
+		let only_in_theirs = theirs
+			.mods
+			.iter()
+			.filter(|m| find(self, &m.name).is_none())
+			.map(|m| m.name.clone())
+			.collect();
+
+		let mut differences = Vec::new();
+		let mut their_positions = Vec::new();
+		for ours in &self.mods {
+			let Some(position) = find(theirs, &ours.name) else {
+				continue;
+			};
+			their_positions.push(position);
+			let other = &theirs.mods[position];
+			if ours.hash != other.hash {
+				differences.push(compare_files(ours, other));
+			}
+		}
+		let order_differs = their_positions.windows(2).any(|pair| pair[0] > pair[1]);
+
+		FingerprintComparison {
+			identical: self.checksum == theirs.checksum,
+			order_differs,
+			only_in_ours,
+			only_in_theirs,
+			differences,
+		}
+	}
+}
+
+fn compare_files(ours: &ModFingerprint, theirs: &ModFingerprint) -> ModDifference {
+	let mut difference = ModDifference {
+		name: ours.name.clone(),
+		changed_files: Vec::new(),
+		only_in_ours: Vec::new(),
+		only_in_theirs: Vec::new(),
+	};
+	for (path, hash) in &ours.files {
+		match theirs.files.get(path) {
+			Some(other) if other != hash => difference.changed_files.push(path.clone()),
+			Some(_) => {}
+			None => difference.only_in_ours.push(path.clone()),
+		}
+	}
+	difference.only_in_theirs = theirs
+		.files
+		.keys()
+		.filter(|path| !ours.files.contains_key(*path))
+		.cloned()
+		.collect();
+	difference
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::manager::ModuleManager;
+	use std::path::Path;
+
+	fn fingerprint(order: &[&str]) -> PlaysetFingerprint {
+		let mut manager = ModuleManager::new();
+		manager.load_mod_dir(Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/resources/mods"));
+		let names: Vec<String> = order.iter().map(|name| name.to_string()).collect();
+		let modules = manager.modules_in_order(&names).unwrap();
+		let modules: Vec<&Module> = modules.iter().map(|m| m.as_ref()).collect();
+		PlaysetFingerprint::from_modules("Europa Universalis IV", &modules)
+	}
+
+	#[test]
+	fn test_identical_fingerprints() {
+		let ours = fingerprint(&["Alpha", "Beta"]);
+		let json = serde_json::to_string(&ours).unwrap();
+		let comparison = ours.compare(&PlaysetFingerprint::parse(&json).unwrap());
+		assert!(comparison.identical);
+		assert!(!comparison.order_differs);
+		assert!(comparison.differences.is_empty());
+
+		let reordered = fingerprint(&["Beta", "Alpha"]);
+		assert_ne!(reordered.checksum, ours.checksum);
+		let comparison = ours.compare(&reordered);
+		assert!(!comparison.identical);
+		assert!(comparison.order_differs);
+		assert!(comparison.differences.is_empty());
+	}
+
+	#[test]
+	fn test_file_differences() {
+		let ours = fingerprint(&["Alpha", "Beta"]);
+		let mut theirs = fingerprint(&["Alpha"]);
+		let alpha = &mut theirs.mods[0];
+		alpha.hash = "0".repeat(32);
+		alpha
+			.files
+			.insert("common/ideas/alpha_ideas.txt".to_string(), "0".repeat(32));
+		alpha.files.remove("common/ideas/00_basic_ideas.txt");
+		alpha
+			.files
+			.insert("common/ideas/extra_ideas.txt".to_string(), "0".repeat(32));
+
+		let comparison = ours.compare(&theirs);
+		assert!(!comparison.identical);
+		assert_eq!(comparison.only_in_ours, vec!["Beta"]);
+		assert!(comparison.only_in_theirs.is_empty());
+		let difference = &comparison.differences[0];
+		assert_eq!(difference.name, "Alpha");
+		assert_eq!(
+			difference.changed_files,
+			vec!["common/ideas/alpha_ideas.txt"]
+		);
+		assert_eq!(
+			difference.only_in_ours,
+			vec!["common/ideas/00_basic_ideas.txt"]
+		);
+		assert_eq!(
+			difference.only_in_theirs,
+			vec!["common/ideas/extra_ideas.txt"]
+		);
+	}
+}
@@ -0,0 +1,221 @@
+use crate::manager::ModuleManager;
+use crate::module::module::Module;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+
+/// 分享格式的版本号，格式不兼容地变化时递增
+const FORMAT_VERSION: u32 = 1;
+
+/// 分享的播放集中的一个 Mod
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SharedMod {
+	pub name: String,
+	pub workshop_id: Option<String>,
+	pub version: Option<String>,
+	pub hash: String, // Module::content_hash
+}
+
+impl SharedMod {
+	pub fn from_module(module: &Module) -> SharedMod {
+		SharedMod {
+			name: module.meta.name.clone(),
+			workshop_id: workshop_id(module),
+			version: module.meta.version.clone(),
+			hash: module.content_hash(),
+		}
+	}
+}
+
+/// 可在玩家之间传递的播放集，只包含已启用的 Mod，顺序即加载顺序
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SharedPlayset {
+	pub format_version: u32,
+	pub game_name: String,
+	pub name: String,
+	pub mods: Vec<SharedMod>,
+}
+
+/// 本地 Mod 与分享条目不一致之处
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ModMismatch {
+	pub name: String, // 本地 Mod 名称
+	pub expected: SharedMod,
+	pub local_version: Option<String>,
+	pub local_hash: String,
+	pub version_mismatch: bool,
+	pub hash_mismatch: bool,
+}
+
+/// 导入分享播放集的结果
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SharedPlaysetImport {
+	pub modules: Vec<String>, // 找到的本地 Mod 名称，按加载顺序排列，包含不一致的 Mod
+	pub missing: Vec<SharedMod>,
+	pub mismatched: Vec<ModMismatch>,
+}
+
+/// 创意工坊 id，优先取 remote_file_id，其次取外层 .mod 文件名 ugc_<id>.mod 中的 id
+pub fn workshop_id(module: &Module) -> Option<String> {
+	if let Some(id) = &module.meta.remote_file_id {
+		return Some(id.clone());
+	}
+	let stem = module.meta.mod_file.as_ref()?.file_stem()?.to_str()?;
+	let id = stem.strip_prefix("ugc_")?;
+	if !id.is_empty() && id.chars().all(|c| c.is_ascii_digit()) {
+		Some(id.to_string())
+	} else {
+		None
+	}
+}
+
+impl SharedPlayset {
+	pub fn from_modules(game_name: &str, name: &str, modules: &[&Module]) -> SharedPlayset {
+		SharedPlayset {
+			format_version: FORMAT_VERSION,
+			game_name: game_name.to_string(),
+			name: name.to_string(),
+			mods: modules
+				.iter()
+				.map(|module| SharedMod::from_module(module))
+				.collect(),
+		}
+	}
+
+	pub fn to_json(&self) -> Result<String, String> {
+		serde_json::to_string_pretty(self).map_err(|e| e.to_string())
+	}
+
+	/// 紧凑的分享字符串，即不带缩进的 JSON 经 URL 安全的 base64 编码
+	pub fn to_share_string(&self) -> Result<String, String> {
+		let json = serde_json::to_vec(self).map_err(|e| e.to_string())?;
+		Ok(URL_SAFE_NO_PAD.encode(json))
+	}
+
+	/// 解析导出的 JSON 文件内容或分享字符串
+	pub fn parse(text: &str) -> Result<SharedPlayset, String> {
+		let text = text.trim();
+		let playset: SharedPlayset = if text.starts_with('{') {
+			serde_json::from_str(text).map_err(|e| format!("Error parsing playset: {}", e))?
+		} else {
+			let json = URL_SAFE_NO_PAD
+				.decode(text.trim_end_matches('='))
+				.map_err(|e| format!("Invalid share string: {}", e))?;
+			serde_json::from_slice(&json).map_err(|e| format!("Invalid share string: {}", e))?
+		};
+		if playset.format_version > FORMAT_VERSION {
+			return Err(format!(
+				"Unsupported playset format version {}",
+				playset.format_version
+			));
+		}
+		Ok(playset)
+	}
+
+	/// 将条目对应到本地 Mod，先按创意工坊 id 匹配，再按名称匹配
+	pub fn match_local(&self, manager: &ModuleManager) -> SharedPlaysetImport {
+		let mut imported = SharedPlaysetImport {
+			modules: Vec::new(),
+			missing: Vec::new(),
+			mismatched: Vec::new(),
+		};
+		for shared in &self.mods {
+			let by_id = shared.workshop_id.as_ref().and_then(|id| {
+				manager
+					.modules()
+					.find(|module| workshop_id(module).as_ref() == Some(id))
+			});
+			let module = match by_id {
+				Some(module) => Some(module.clone()),
+				None => manager.find_module(&shared.name),
+			};
+			let Some(module) = module else {
+				imported.missing.push(shared.clone());
+				continue;
+			};
+
+			let local_hash = module.content_hash();
+			let version_mismatch = module.meta.version != shared.version;
+			let hash_mismatch = local_hash != shared.hash;
+			if version_mismatch || hash_mismatch {
+				imported.mismatched.push(ModMismatch {
+					name: module.meta.name.clone(),
+					expected: shared.clone(),
+					local_version: module.meta.version.clone(),
+					local_hash,
+					version_mismatch,
+					hash_mismatch,
+				});
+			}
+			imported.modules.push(module.meta.name.clone());
+		}
+		imported
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::path::Path;
+
+	fn manager() -> ModuleManager {
+		let mut manager = ModuleManager::new();
+		manager.load_mod_dir(Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/resources/mods"));
+		manager
+	}
+
+	fn export(manager: &ModuleManager) -> SharedPlayset {
+		let modules = manager
+			.modules_in_order(&["Beta".to_string(), "Alpha".to_string()])
+			.unwrap();
+		let modules: Vec<&Module> = modules.iter().map(|m| m.as_ref()).collect();
+		SharedPlayset::from_modules("Europa Universalis IV", "Main", &modules)
+	}
+
+	#[test]
+	fn test_export_round_trip() {
+		let manager = manager();
+		let playset = export(&manager);
+		assert_eq!(playset.mods[0].name, "Beta");
+		assert_eq!(playset.mods[0].version.as_deref(), Some("2.1"));
+		assert_eq!(playset.mods[0].hash.len(), 32);
+
+		let json = playset.to_json().unwrap();
+		assert_eq!(SharedPlayset::parse(&json).unwrap(), playset);
+		let share = playset.to_share_string().unwrap();
+		assert!(!share.contains(['{', '/', '+']));
+		assert_eq!(SharedPlayset::parse(&share).unwrap(), playset);
+		assert!(SharedPlayset::parse("not a playset").is_err());
+	}
+
+	#[test]
+	fn test_match_local() {
+		let manager = manager();
+		let mut playset = export(&manager);
+		let imported = playset.match_local(&manager);
+		assert_eq!(imported.modules, vec!["Beta", "Alpha"]);
+		assert!(imported.missing.is_empty());
+		assert!(imported.mismatched.is_empty());
+
+		playset.mods[0].version = Some("2.2".to_string());
+		playset.mods[1].hash = "0".repeat(32);
+		playset.mods.push(SharedMod {
+			name: "Gamma".to_string(),
+			workshop_id: Some("123456".to_string()),
+			version: None,
+			hash: "0".repeat(32),
+		});
+		let imported = playset.match_local(&manager);
+		assert_eq!(imported.modules, vec!["Beta", "Alpha"]);
+		assert_eq!(imported.missing[0].name, "Gamma");
+		let mismatches: Vec<(&str, bool, bool)> = imported
+			.mismatched
+			.iter()
+			.map(|m| (m.name.as_str(), m.version_mismatch, m.hash_mismatch))
+			.collect();
+		assert_eq!(
+			mismatches,
+			vec![("Beta", true, false), ("Alpha", false, true)]
+		);
+	}
+}
@@ -0,0 +1,215 @@
+use crate::config::game::GameConfig;
+use crate::lang::vdf::{parse_vdf, VdfObject, VdfValue};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// 支持自动检测的 Paradox 游戏：Steam appid 与游戏名称，名称同时是安装目录名与文档目录名
+pub(crate) const PARADOX_GAMES: [(u32, &str); 5] = [
+	(236850, "Europa Universalis IV"),
+	(1158310, "Crusader Kings III"),
+	(394360, "Hearts of Iron IV"),
+	(281990, "Stellaris"),
+	(529340, "Victoria 3"),
+];
+
+/// 检测到的游戏安装，`config` 可直接用于 setup_game_config
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct DetectedGame {
+	pub(crate) app_id: u32,
+	pub(crate) config: GameConfig,
+	pub(crate) library: PathBuf,
+	pub(crate) proton: bool, // 用户目录位于 Proton 前缀内
+}
+
+/// Linux 上 Steam 的常见安装位置，包括 Flatpak 版本
+pub(crate) fn steam_roots(home: &Path) -> Vec<PathBuf> {
+	let mut roots: Vec<PathBuf> = Vec::new();
+	for candidate in [
+		".steam/steam",
+		".steam/root",
+		".local/share/Steam",
+		".var/app/com.valvesoftware.Steam/.local/share/Steam",
+	] {
+		let path = home.join(candidate);
+		if !path.join("steamapps").is_dir() {
+			continue;
+		}
+		// ~/.steam/steam 通常是指向 ~/.local/share/Steam 的符号链接
+		let path = path.canonicalize().unwrap_or(path);
+		if !roots.contains(&path) {
+			roots.push(path);
+		}
+	}
+	roots
+}
+
+/// 读取 steamapps/libraryfolders.vdf 中列出的全部库目录，Steam 根目录本身总是第一个库
+pub(crate) fn library_folders(steam_root: &Path) -> Vec<PathBuf> {
+	let mut libraries = vec![steam_root.to_path_buf()];
+	let root = match read_vdf(&steam_root.join("steamapps").join("libraryfolders.vdf")) {
+		Ok(root) => root,
+		Err(_) => return libraries,
+	};
+	let Some(folders) = root.get_object("libraryfolders") else {
+		return libraries;
+	};
+	for (key, folder) in folders.iter() {
+		// 新格式中每个库是带 path 字段的对象，旧格式直接以序号作为键、路径作为值
+		let path = match folder {
+			VdfValue::Object(folder) => folder.get_str("path"),
+			VdfValue::String(path) if key.chars().all(|c| c.is_ascii_digit()) => {
+				Some(path.as_str())
+			}
+			VdfValue::String(_) => None,
+		};
+		let Some(path) = path else {
+			continue;
+		};
+		let path = PathBuf::from(path);
+		let path = path.canonicalize().unwrap_or(path);
+		if !libraries.contains(&path) {
+			libraries.push(path);
+		}
+	}
+	libraries
+}
+
+pub(crate) fn read_vdf(path: &Path) -> Result<VdfObject, String> {
+	let content = std::fs::read_to_string(path)
+		.map_err(|e| format!("Error reading {}: {}", path.display(), e))?;
+	parse_vdf(&content).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+/// 在 appmanifest_<appid>.acf 中读取游戏的安装目录名
+fn install_dir(library: &Path, app_id: u32) -> Option<String> {
+	let manifest = library
+		.join("steamapps")
+		.join(format!("appmanifest_{}.acf", app_id));
+	let root = read_vdf(&manifest).ok()?;
+	Some(
+		root.get_object("AppState")?
+			.get_str("installdir")?
+			.to_string(),
+	)
+}
+
+/// 游戏的用户目录：优先使用原生版本的目录，其次是 Proton 前缀中的文档目录
+fn user_dir(home: &Path, library: &Path, app_id: u32, game_name: &str) -> (PathBuf, bool) {
+	let native = home
+		.join(".local/share/Paradox Interactive")
+		.join(game_name);
+	let prefix = library
+		.join("steamapps/compatdata")
+		.join(app_id.to_string())
+		.join("pfx");
+	let proton = prefix
+		.join("drive_c/users/steamuser/Documents/Paradox Interactive")
+		.join(game_name);
+	if native.is_dir() {
+		(native, false)
+	} else if proton.is_dir() || prefix.is_dir() {
+		(proton, true)
+	} else {
+		(native, false)
+	}
+}
+
+/// 在给定的 Steam 根目录下查找已安装的 Paradox 游戏
+pub(crate) fn detect_games(home: &Path, steam_roots: &[PathBuf]) -> Vec<DetectedGame> {
+	let mut libraries: Vec<PathBuf> = Vec::new();
+	for root in steam_roots {
+		for library in library_folders(root) {
+			if !libraries.contains(&library) {
+				libraries.push(library);
+			}
+		}
+	}
+
+	let mut detected = Vec::new();
+	for (app_id, game_name) in PARADOX_GAMES {
+		let found = libraries.iter().find_map(|library| {
+			let game_dir = library
+				.join("steamapps/common")
+				.join(install_dir(library, app_id)?);
+			game_dir.is_dir().then_some((library, game_dir))
+		});
+		let Some((library, game_dir)) = found else {
+			continue;
+		};
+		let (user_dir, proton) = user_dir(home, library, app_id, game_name);
+		detected.push(DetectedGame {
+			app_id,
+			config: GameConfig::new(game_name.to_string(), game_dir, user_dir.join("mod")),
+			library: library.clone(),
+			proton,
+		});
+	}
+	detected
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn write(path: &Path, content: &str) {
+		std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+		std::fs::write(path, content).unwrap();
+	}
+
+	#[test]
+	fn test_detect_games() {
+		let root = std::env::temp_dir().join(format!("pmm_steam_{}", std::process::id()));
+		let home = root.join("home");
+		let steam = home.join(".local/share/Steam");
+		let games = root.join("games");
+		write(
+			&steam.join("steamapps/libraryfolders.vdf"),
+			&format!(
+				"\"libraryfolders\"\n{{\n\t\"0\"\n\t{{\n\t\t\"path\"\t\t\"{}\"\n\t\t\"apps\"\n\t\t{{\n\t\t\t\"236850\"\t\t\"123\"\n\t\t}}\n\t}}\n\t\"1\"\n\t{{\n\t\t\"path\"\t\t\"{}\"\n\t}}\n}}\n",
+				steam.display(),
+				games.display()
+			),
+		);
+		write(
+			&steam.join("steamapps/appmanifest_236850.acf"),
+			"\"AppState\"\n{\n\t\"appid\"\t\t\"236850\"\n\t\"installdir\"\t\t\"Europa Universalis IV\"\n}\n",
+		);
+		std::fs::create_dir_all(steam.join("steamapps/common/Europa Universalis IV")).unwrap();
+		std::fs::create_dir_all(
+			home.join(".local/share/Paradox Interactive/Europa Universalis IV/mod"),
+		)
+		.unwrap();
+
+		write(
+			&games.join("steamapps/appmanifest_529340.acf"),
+			"\"AppState\"\n{\n\t\"appid\"\t\t\"529340\"\n\t\"installdir\"\t\t\"Victoria 3\"\n}\n",
+		);
+		std::fs::create_dir_all(games.join("steamapps/common/Victoria 3")).unwrap();
+		std::fs::create_dir_all(games.join("steamapps/compatdata/529340/pfx")).unwrap();
+		// 只有清单而没有安装目录的游戏不算已安装
+		write(
+			&games.join("steamapps/appmanifest_281990.acf"),
+			"\"AppState\"\n{\n\t\"installdir\"\t\t\"Stellaris\"\n}\n",
+		);
+
+		let roots = steam_roots(&home);
+		assert_eq!(roots.len(), 1);
+		let detected = detect_games(&home, &roots);
+		let summary: Vec<(u32, &str, bool)> = detected
+			.iter()
+			.map(|game| (game.app_id, game.config.game_name.as_str(), game.proton))
+			.collect();
+		assert_eq!(
+			summary,
+			vec![
+				(236850, "Europa Universalis IV", false),
+				(529340, "Victoria 3", true)
+			]
+		);
+		assert!(detected[0].config.validate().is_ok());
+		assert!(detected[1].config.mod_dir.ends_with(
+			"compatdata/529340/pfx/drive_c/users/steamuser/Documents/Paradox Interactive/Victoria 3/mod"
+		));
+		std::fs::remove_dir_all(&root).unwrap();
+	}
+}
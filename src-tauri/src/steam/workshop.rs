@@ -0,0 +1,231 @@
+use crate::config::game::GameConfig;
+use crate::manager::ModuleManager;
+use crate::steam::detect::{read_vdf, PARADOX_GAMES};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// 游戏所在 Steam 库中的创意工坊位置
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct WorkshopLocation {
+	pub(crate) steamapps: PathBuf,
+	pub(crate) app_id: u32,
+}
+
+/// 创意工坊物品的订阅状态
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum WorkshopStatus {
+	Installed,
+	NeedsUpdate,   // Steam 已记录到更新的版本，但尚未下载
+	NotDownloaded, // 清单中有记录，但内容目录不存在
+	Untracked,     // 内容目录存在，但清单中没有记录
+}
+
+/// 创意工坊中的一个物品
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub(crate) struct WorkshopItem {
+	pub(crate) id: String,
+	pub(crate) path: PathBuf,
+	pub(crate) size: Option<u64>,
+	pub(crate) time_updated: Option<u64>, // 已安装版本的更新时间，Unix 时间戳
+	pub(crate) status: WorkshopStatus,
+	pub(crate) module: Option<String>, // 通过 remote_file_id 对应的本地 Mod 名称
+}
+
+/// appworkshop_<appid>.acf 中一个物品的记录
+#[derive(Debug, Clone, Default, PartialEq)]
+struct ManifestItem {
+	size: Option<u64>,
+	time_updated: Option<u64>,
+	latest_time_updated: Option<u64>, // WorkshopItemDetails 中记录的最新版本
+	installed: bool,
+}
+
+impl WorkshopLocation {
+	/// 游戏目录位于 <库>/steamapps/common/<游戏> 时，返回该库中的创意工坊位置
+	pub(crate) fn from_game_config(game_config: &GameConfig) -> Option<WorkshopLocation> {
+		let (app_id, _) = PARADOX_GAMES
+			.iter()
+			.find(|(_, name)| *name == game_config.game_name)?;
+		let common = game_config.game_dir.parent()?;
+		let steamapps = common.parent()?;
+		if common.file_name()? != "common" || steamapps.file_name()? != "steamapps" {
+			return None;
+		}
+		Some(WorkshopLocation {
+			steamapps: steamapps.to_path_buf(),
+			app_id: *app_id,
+		})
+	}
+
+	/// steamapps/workshop/content/<appid>，每个子目录对应一个物品
+	pub(crate) fn content_dir(&self) -> PathBuf {
+		self.steamapps
+			.join("workshop/content")
+			.join(self.app_id.to_string())
+	}
+
+	pub(crate) fn manifest_path(&self) -> PathBuf {
+		self.steamapps
+			.join("workshop")
+			.join(format!("appworkshop_{}.acf", self.app_id))
+	}
+
+	/// 内容目录下的全部物品目录，按 id 排列
+	pub(crate) fn item_dirs(&self) -> Vec<(String, PathBuf)> {
+		let Ok(entries) = std::fs::read_dir(self.content_dir()) else {
+			return Vec::new();
+		};
+		let mut dirs: Vec<(String, PathBuf)> = entries
+			.filter_map(|entry| entry.ok())
+			.map(|entry| entry.path())
+			.filter(|path| path.is_dir())
+			.filter_map(|path| Some((path.file_name()?.to_str()?.to_string(), path)))
+			.collect();
+		dirs.sort();
+		dirs
+	}
+
+	/// 合并内容目录与清单中的记录，并通过 remote_file_id 对应到管理器中的 Mod
+	pub(crate) fn scan(&self, manager: &ModuleManager) -> Result<Vec<WorkshopItem>, String> {
+		let mut manifest = match self.manifest_path() {
+			path if path.exists() => read_manifest(&path)?,
+			_ => BTreeMap::new(),
+		};
+		let mut dirs: BTreeMap<String, PathBuf> = self.item_dirs().into_iter().collect();
+		let ids: Vec<String> = manifest
+			.keys()
+			.chain(dirs.keys())
+			.cloned()
+			.collect::<std::collections::BTreeSet<_>>()
+			.into_iter()
+			.collect();
+
+		let content_dir = self.content_dir();
+		let mut items = Vec::new();
+		for id in ids {
+			let record = manifest.remove(&id);
+			let path = dirs.remove(&id);
+			let status = match (&record, &path) {
+				(Some(record), Some(_)) if record.installed => {
+					match (record.time_updated, record.latest_time_updated) {
+						(Some(installed), Some(latest)) if latest > installed => {
+							WorkshopStatus::NeedsUpdate
+						}
+						_ => WorkshopStatus::Installed,
+					}
+				}
+				(_, None) => WorkshopStatus::NotDownloaded,
+				(_, Some(_)) => WorkshopStatus::Untracked,
+			};
+			let module = manager
+				.modules()
+				.find(|module| module.meta.remote_file_id.as_deref() == Some(id.as_str()))
+				.map(|module| module.meta.name.clone());
+			let record = record.unwrap_or_default();
+			items.push(WorkshopItem {
+				path: path.unwrap_or_else(|| content_dir.join(&id)),
+				id,
+				size: record.size,
+				time_updated: record.time_updated,
+				status,
+				module,
+			});
+		}
+		Ok(items)
+	}
+}
+
+fn read_manifest(path: &Path) -> Result<BTreeMap<String, ManifestItem>, String> {
+	let root = read_vdf(path)?;
+	let Some(workshop) = root.get_object("AppWorkshop") else {
+		return Err(format!("Missing AppWorkshop in {}", path.display()));
+	};
+	let number = |object: &crate::lang::vdf::VdfObject, key: &str| {
+		object.get_str(key).and_then(|value| value.parse().ok())
+	};
+
+	let mut items: BTreeMap<String, ManifestItem> = BTreeMap::new();
+	if let Some(installed) = workshop.get_object("WorkshopItemsInstalled") {
+		for (id, item) in installed.iter() {
+			let Some(item) = item.as_object() else {
+				continue;
+			};
+			let entry = items.entry(id.to_string()).or_default();
+			entry.size = number(item, "size");
+			entry.time_updated = number(item, "timeupdated");
+			entry.installed = true;
+		}
+	}
+	if let Some(details) = workshop.get_object("WorkshopItemDetails") {
+		for (id, item) in details.iter() {
+			let Some(item) = item.as_object() else {
+				continue;
+			};
+			items.entry(id.to_string()).or_default().latest_time_updated =
+				number(item, "timeupdated");
+		}
+	}
+	Ok(items)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn library() -> PathBuf {
+		Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/resources/steam/library")
+	}
+
+	fn location() -> WorkshopLocation {
+		let game_config = GameConfig::new(
+			"Europa Universalis IV".to_string(),
+			library().join("steamapps/common/Europa Universalis IV"),
+			Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/resources/mods"),
+		);
+		WorkshopLocation::from_game_config(&game_config).unwrap()
+	}
+
+	#[test]
+	fn test_workshop_location() {
+		let location = location();
+		assert_eq!(location.app_id, 236850);
+		assert_eq!(
+			location.content_dir(),
+			library().join("steamapps/workshop/content/236850")
+		);
+		let ids: Vec<String> = location.item_dirs().into_iter().map(|(id, _)| id).collect();
+		assert_eq!(ids, vec!["1234567890", "3456789012"]);
+
+		let elsewhere = GameConfig::new(
+			"Europa Universalis IV".to_string(),
+			PathBuf::from("/opt/eu4"),
+			PathBuf::from("/opt/eu4/mod"),
+		);
+		assert!(WorkshopLocation::from_game_config(&elsewhere).is_none());
+	}
+
+	#[test]
+	fn test_scan() {
+		let location = location();
+		let mut manager = ModuleManager::new();
+		manager.load_workshop_dir(&location);
+		let items = location.scan(&manager).unwrap();
+		let summary: Vec<(&str, WorkshopStatus, Option<&str>)> = items
+			.iter()
+			.map(|item| (item.id.as_str(), item.status, item.module.as_deref()))
+			.collect();
+		assert_eq!(
+			summary,
+			vec![
+				("1234567890", WorkshopStatus::NeedsUpdate, Some("Delta")),
+				("2345678901", WorkshopStatus::NotDownloaded, None),
+				("3456789012", WorkshopStatus::Untracked, Some("Epsilon")),
+			]
+		);
+		assert_eq!(items[0].size, Some(52428));
+		assert_eq!(items[0].time_updated, Some(1700000000));
+		assert!(items[2].size.is_none());
+	}
+}
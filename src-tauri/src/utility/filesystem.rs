@@ -32,3 +32,44 @@ pub fn visit_dir<P: AsRef<Path>>(path: P) -> Result<Vec<PathBuf>, io::Error> {
 
 	Ok(files)
 }
+
+/// 将 `root` 下的文件路径转换为规范化的虚拟路径
+///
+/// 虚拟路径使用 `/` 分隔并统一转为小写，以便匹配来自 Windows 的大小写不一致的 Mod
+pub fn to_virtual_path<P: AsRef<Path>, Q: AsRef<Path>>(root: P, path: Q) -> Option<String> {
+	let relative = path.as_ref().strip_prefix(root.as_ref()).ok()?;
+	let segments = relative
+		.components()
+		.map(|component| component.as_os_str().to_str())
+		.collect::<Option<Vec<&str>>>()?;
+	if segments.is_empty() {
+		return None;
+	}
+	Some(segments.join("/").replace('\\', "/").to_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_to_virtual_path() {
+		let root = Path::new("/mods/beta");
+		assert_eq!(
+			to_virtual_path(
+				root,
+				root.join("Common").join("Ideas").join("00_basic_ideas.txt")
+			),
+			Some("common/ideas/00_basic_ideas.txt".to_string())
+		);
+		assert_eq!(
+			to_virtual_path(root, root.join("events\\Flavor.txt")),
+			Some("events/flavor.txt".to_string())
+		);
+		assert_eq!(
+			to_virtual_path(root, Path::new("/elsewhere/file.txt")),
+			None
+		);
+		assert_eq!(to_virtual_path(root, root), None);
+	}
+}
@@ -0,0 +1,215 @@
+use crate::config::game::GameConfig;
+use crate::module::category::Category;
+use crate::module::component::Component;
+use crate::module::module::Module;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// 文件的来源：原版游戏或某个 Mod
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+#[serde(tag = "kind", content = "module", rename_all = "snake_case")]
+pub enum FileSource {
+	Vanilla,
+	Module(String),
+}
+
+/// 最终生效的文件
+#[derive(Debug, Clone)]
+pub struct EffectiveFile<'a> {
+	pub source: FileSource,
+	pub component: &'a Component,
+	pub shadowed: Vec<(FileSource, &'a Component)>, // 被覆盖的同名文件，按加载顺序排列
+}
+
+/// 因 replace_path 而被隐藏的文件
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HiddenFile {
+	pub relative_path: String,
+	pub source: FileSource,
+	pub hidden_by: String,    // 声明 replace_path 的 Mod
+	pub replace_path: String, // 导致隐藏的 replace_path
+}
+
+/// 原版与按加载顺序排列的 Mod 叠加后，游戏实际读取的文件集合
+#[derive(Debug, Default)]
+pub struct EffectiveFileSet<'a> {
+	files: BTreeMap<String, EffectiveFile<'a>>,
+	hidden: Vec<HiddenFile>,
+}
+
+impl<'a> EffectiveFileSet<'a> {
+	/// 依次叠加原版文件与各 Mod 的文件
+	///
+	/// 每个 Mod 先应用自己的 replace_path，清空原版与之前的 Mod 在这些目录中的文件，
+	/// 再加入自己的文件；同名文件由后加载的 Mod 覆盖
+	pub fn build(vanilla: &'a [Component], modules: &[&'a Module]) -> EffectiveFileSet<'a> {
+		let mut set = EffectiveFileSet::default();
+		for component in vanilla {
+			set.insert(FileSource::Vanilla, component);
+		}
+		for module in modules {
+			for replace_path in &module.meta.replace_paths {
+				set.replace(&module.meta.name, replace_path);
+			}
+			for component in &module.components {
+				set.insert(FileSource::Module(module.meta.name.clone()), component);
+			}
+		}
+		set
+	}
+
+	fn insert(&mut self, source: FileSource, component: &'a Component) {
+		// Mod 根目录下的 descriptor.mod 与原版根目录下的程序文件不属于游戏内容
+		if component.category() == &Category::Root {
+			return;
+		}
+		let mut file = EffectiveFile {
+			source,
+			component,
+			shadowed: Vec::new(),
+		};
+		if let Some(previous) = self.files.remove(component.relative_path()) {
+			file.shadowed = previous.shadowed;
+			file.shadowed.push((previous.source, previous.component));
+		}
+		self.files
+			.insert(component.relative_path().to_string(), file);
+	}
+
+	/// replace_path 只作用于该目录下的文件，不包括子目录
+	fn replace(&mut self, module_name: &str, replace_path: &str) {
+		let replaced: Vec<String> = self
+			.files
+			.keys()
+			.filter(|path| parent_folder(path) == replace_path)
+			.cloned()
+			.collect();
+		for relative_path in replaced {
+			let file = self.files.remove(&relative_path).unwrap();
+			self.hidden.push(HiddenFile {
+				relative_path,
+				source: file.source,
+				hidden_by: module_name.to_string(),
+				replace_path: replace_path.to_string(),
+			});
+		}
+	}
+
+	/// 按虚拟路径排序的生效文件
+	pub fn files(&self) -> impl Iterator<Item = (&str, &EffectiveFile<'a>)> {
+		self.files.iter().map(|(path, file)| (path.as_str(), file))
+	}
+
+	pub fn get(&self, relative_path: &str) -> Option<&EffectiveFile<'a>> {
+		self.files.get(relative_path)
+	}
+
+	/// 虚拟路径是否有生效的文件，路径不区分大小写
+	pub fn contains(&self, relative_path: &str) -> bool {
+		self.files
+			.contains_key(&relative_path.replace('\\', "/").to_lowercase())
+	}
+
+	pub fn hidden(&self) -> &[HiddenFile] {
+		&self.hidden
+	}
+}
+
+fn parent_folder(relative_path: &str) -> &str {
+	match relative_path.rfind('/') {
+		Some(index) => &relative_path[..index],
+		None => "",
+	}
+}
+
+/// 读取游戏的全部原版文件
+pub fn load_vanilla(game_config: &GameConfig) -> Result<Vec<Component>, String> {
+	Component::get_components_from_dir(game_config.vanilla_dir())
+		.map_err(|e| format!("Error reading vanilla files: {}", e))
+}
+
+/// 计算 replace_path 隐藏的原版与 Mod 文件
+pub fn find_hidden_files(vanilla: &[Component], modules: &[&Module]) -> Vec<HiddenFile> {
+	EffectiveFileSet::build(vanilla, modules).hidden().to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::manager::ModuleManager;
+	use std::path::Path;
+
+	fn resources() -> std::path::PathBuf {
+		Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/resources")
+	}
+
+	#[test]
+	fn test_replace_path() {
+		let vanilla = Component::get_components_from_dir(resources().join("vanilla")).unwrap();
+		let mut manager = ModuleManager::new();
+		manager.load_mod_dir(resources().join("mods"));
+		let modules = manager
+			.modules_in_order(&["Alpha".to_string(), "Beta".to_string()])
+			.unwrap();
+		let modules: Vec<&Module> = modules.iter().map(|m| m.as_ref()).collect();
+		let set = EffectiveFileSet::build(&vanilla, &modules);
+
+		let mut hidden: Vec<(&str, &FileSource)> = set
+			.hidden()
+			.iter()
+			.map(|h| (h.relative_path.as_str(), &h.source))
+			.collect();
+		hidden.sort_by_key(|(path, _)| *path);
+		assert_eq!(
+			hidden,
+			vec![
+				(
+					"history/countries/alp - alpha.txt",
+					&FileSource::Module("Alpha".to_string())
+				),
+				("history/countries/swe - sweden.txt", &FileSource::Vanilla),
+			]
+		);
+		assert!(set.hidden().iter().all(|h| h.hidden_by == "Beta"));
+
+		// Beta 自己在被替换目录中的文件与其他目录中的原版文件仍然生效
+		assert_eq!(
+			set.get("history/countries/swe - sweden.txt")
+				.unwrap()
+				.source,
+			FileSource::Module("Beta".to_string())
+		);
+		assert_eq!(
+			set.get("history/provinces/1 - uppland.txt").unwrap().source,
+			FileSource::Vanilla
+		);
+		assert!(set.get("descriptor.mod").is_none());
+
+		let ideas = set.get("common/ideas/00_basic_ideas.txt").unwrap();
+		assert_eq!(ideas.source, FileSource::Module("Beta".to_string()));
+		assert_eq!(
+			ideas
+				.shadowed
+				.iter()
+				.map(|(source, _)| source.clone())
+				.collect::<Vec<_>>(),
+			vec![FileSource::Vanilla, FileSource::Module("Alpha".to_string())]
+		);
+	}
+
+	#[test]
+	fn test_replace_path_only_affects_earlier_sources() {
+		let mut manager = ModuleManager::new();
+		manager.load_mod_dir(resources().join("mods"));
+		let modules = manager
+			.modules_in_order(&["Beta".to_string(), "Alpha".to_string()])
+			.unwrap();
+		let modules: Vec<&Module> = modules.iter().map(|m| m.as_ref()).collect();
+		let set = EffectiveFileSet::build(&[], &modules);
+		assert!(set.hidden().is_empty());
+		assert_eq!(
+			set.get("history/countries/alp - alpha.txt").unwrap().source,
+			FileSource::Module("Alpha".to_string())
+		);
+	}
+}
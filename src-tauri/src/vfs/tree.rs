@@ -0,0 +1,140 @@
+use super::effective::{load_vanilla, EffectiveFileSet, FileSource, HiddenFile};
+use crate::config::game::GameConfig;
+use crate::module::category::Category;
+use crate::module::component::Component;
+use crate::module::module::Module;
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// 文件的一个来源及其在磁盘上的位置
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FileOrigin {
+	pub source: FileSource,
+	pub path: PathBuf,
+}
+
+impl FileOrigin {
+	fn new(source: &FileSource, component: &Component) -> FileOrigin {
+		FileOrigin {
+			source: source.clone(),
+			path: component.path().to_path_buf(),
+		}
+	}
+}
+
+/// 游戏最终看到的某个虚拟路径
+#[derive(Debug, Clone, Serialize)]
+pub struct ResolvedFile {
+	pub relative_path: String,
+	pub category: Category,
+	pub winner: FileOrigin,
+	pub shadowed: Vec<FileOrigin>, // 被覆盖的来源，按加载顺序排列
+}
+
+/// 原版与已启用 Mod 合并后的虚拟文件系统
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct VirtualTree {
+	pub files: Vec<ResolvedFile>, // 按虚拟路径排序
+	pub hidden: Vec<HiddenFile>,  // 被 replace_path 隐藏的文件
+}
+
+impl VirtualTree {
+	pub fn from_effective(set: &EffectiveFileSet) -> VirtualTree {
+		let files = set
+			.files()
+			.map(|(relative_path, file)| ResolvedFile {
+				relative_path: relative_path.to_string(),
+				category: file.component.category().clone(),
+				winner: FileOrigin::new(&file.source, file.component),
+				shadowed: file
+					.shadowed
+					.iter()
+					.map(|(source, component)| FileOrigin::new(source, component))
+					.collect(),
+			})
+			.collect();
+		VirtualTree {
+			files,
+			hidden: set.hidden().to_vec(),
+		}
+	}
+
+	/// 读取游戏的原版文件，与按加载顺序排列的已启用 Mod 合并
+	pub fn resolve(game_config: &GameConfig, modules: &[&Module]) -> Result<VirtualTree, String> {
+		let vanilla = load_vanilla(game_config)?;
+		let set = EffectiveFileSet::build(&vanilla, modules);
+		Ok(VirtualTree::from_effective(&set))
+	}
+
+	/// 查找某个虚拟路径的来源
+	pub fn find(&self, relative_path: &str) -> Option<&ResolvedFile> {
+		let relative_path = relative_path.replace('\\', "/").to_lowercase();
+		self.files
+			.binary_search_by(|file| file.relative_path.as_str().cmp(&relative_path))
+			.ok()
+			.map(|index| &self.files[index])
+	}
+
+	/// 列出某个目录下的直接子文件
+	pub fn list_dir(&self, folder: &str) -> Vec<&ResolvedFile> {
+		let prefix = format!("{}/", folder.trim_end_matches('/').to_lowercase());
+		self.files
+			.iter()
+			.filter(|file| match file.relative_path.strip_prefix(&prefix) {
+				Some(name) => !name.contains('/'),
+				None => false,
+			})
+			.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::manager::ModuleManager;
+	use std::path::Path;
+
+	#[test]
+	fn test_resolve_virtual_tree() {
+		let resources = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/resources");
+		let game_config = GameConfig::new(
+			"Europa Universalis IV".to_string(),
+			resources.join("vanilla"),
+			resources.join("mods"),
+		);
+		let manager = ModuleManager::from_game_config(&game_config);
+		let modules = manager
+			.modules_in_order(&["Alpha".to_string(), "Beta".to_string()])
+			.unwrap();
+		let modules: Vec<&Module> = modules.iter().map(|m| m.as_ref()).collect();
+		let tree = VirtualTree::resolve(&game_config, &modules).unwrap();
+
+		let ideas = tree.find("Common/Ideas/00_basic_ideas.txt").unwrap();
+		assert_eq!(ideas.winner.source, FileSource::Module("Beta".to_string()));
+		assert!(ideas
+			.winner
+			.path
+			.ends_with("Common/Ideas/00_basic_ideas.txt"));
+		assert_eq!(ideas.shadowed.len(), 2);
+		assert_eq!(ideas.shadowed[0].source, FileSource::Vanilla);
+		assert_eq!(
+			ideas.shadowed[0].path,
+			resources.join("vanilla/common/ideas/00_basic_ideas.txt")
+		);
+
+		let uppland = tree.find("history/provinces/1 - uppland.txt").unwrap();
+		assert_eq!(uppland.winner.source, FileSource::Vanilla);
+		assert!(uppland.shadowed.is_empty());
+
+		assert!(tree.find("history/countries/alp - alpha.txt").is_none());
+		assert_eq!(tree.hidden.len(), 2);
+
+		let countries = tree.list_dir("history/countries");
+		assert_eq!(countries.len(), 1);
+		assert_eq!(
+			countries[0].winner.source,
+			FileSource::Module("Beta".to_string())
+		);
+		assert_eq!(tree.list_dir("common").len(), 0);
+	}
+}
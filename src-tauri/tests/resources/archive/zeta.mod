@@ -0,0 +1,4 @@
+version="1.0.0"
+name="Zeta"
+supported_version="1.37.*"
+archive="archive/zeta.zip"
@@ -0,0 +1,7 @@
+version="1.0.0"
+tags={
+	"Gameplay"
+}
+name="Alpha"
+supported_version="1.37.*"
+path="mods/alpha"
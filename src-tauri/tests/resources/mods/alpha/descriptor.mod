@@ -0,0 +1,6 @@
+version="1.0.0"
+tags={
+	"Gameplay"
+}
+name="Alpha"
+supported_version="1.37.*"
@@ -0,0 +1,8 @@
+version="2.1"
+tags={
+	"Gameplay"
+}
+name="Beta"
+supported_version="1.37.*"
+path="mods/beta"
+replace_path="history/countries"
@@ -0,0 +1 @@
+NDefines.NCountry.MAX_IDEA_GROUPS = 8
@@ -0,0 +1,7 @@
+version="2.1"
+tags={
+	"Gameplay"
+}
+name="Beta"
+supported_version="1.37.*"
+replace_path="history/countries"
@@ -0,0 +1,7 @@
+version="3.0"
+tags={
+	"Gameplay"
+}
+name="Delta"
+supported_version="1.37.*"
+remote_file_id="1234567890"
@@ -0,0 +1,3 @@
+version="1.0"
+name="Epsilon"
+supported_version="1.37.*"